@@ -1,13 +1,21 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
 use buildstructor::buildstructor;
-use image::{Rgb, RgbImage};
-use indicatif::ParallelProgressIterator;
+use image::Rgb32FImage;
+use indicatif::ProgressBar;
 use na::{Point3, Unit, Vector3};
-use rayon::prelude::*;
 
+use crate::environments::{Environment, Gradient};
+use crate::film::{Film, FilmSample, SamplingSettings};
+use crate::filter::{Filter, FilterKind};
 use crate::geometry::interval::Interval;
 use crate::geometry::ray::{Hit, Hittable, Ray};
 use crate::materials::Scattered;
-use crate::util::random_in_unit_disk;
+use crate::samplers::{Halton, Independent, Sampler, SamplerKind, Sobol, Stratified};
+use crate::tiles::{tiles, TileOrder};
+use crate::util::sample_unit_disk;
 
 #[allow(clippy::struct_field_names)]
 #[derive(Debug)]
@@ -23,6 +31,24 @@ pub struct Camera {
     defocus_disk_v: Vector3<f64>,
     samples_per_pixel: usize,
     max_depth: usize,
+    environment: Arc<dyn Environment>,
+    /// The seed every sample's random numbers are derived from.
+    seed: u64,
+    sampler: SamplerKind,
+    filter: Filter,
+    /// The width and height of the tiles the image is rendered in, in pixels.
+    tile_size: u32,
+    tile_order: TileOrder,
+    /// How long a progressive render may keep adding passes for.
+    time_limit: Option<Duration>,
+    /// The relative error every pixel must be under for a progressive render to stop early.
+    noise_threshold: Option<f64>,
+    /// The number of samples every pixel gets before adaptive sampling can stop sampling it,
+    /// if adaptive sampling is on at all.
+    min_samples_per_pixel: Option<usize>,
+    /// The times the shutter opens and closes, which each ray's time is picked from between.
+    shutter_open: f64,
+    shutter_close: f64,
 
     camera_u: Unit<Vector3<f64>>,
     camera_v: Unit<Vector3<f64>>,
@@ -32,6 +58,7 @@ pub struct Camera {
 #[buildstructor]
 impl Camera {
     #[builder]
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         image_size: (u32, u32),
         vertical_fov: Option<f64>,
@@ -42,6 +69,18 @@ impl Camera {
         defocus_angle: Option<f64>,
         samples_per_pixel: Option<usize>,
         max_depth: Option<usize>,
+        environment: Option<Arc<dyn Environment>>,
+        seed: Option<u64>,
+        sampler: Option<SamplerKind>,
+        filter: Option<FilterKind>,
+        filter_radius: Option<f64>,
+        tile_size: Option<u32>,
+        tile_order: Option<TileOrder>,
+        time_limit: Option<Duration>,
+        noise_threshold: Option<f64>,
+        min_samples_per_pixel: Option<usize>,
+        shutter_open: Option<f64>,
+        shutter_close: Option<f64>,
     ) -> Self {
         let (image_width, image_height) = image_size;
         // The vertical equivalent of 90 deg. FOV
@@ -68,6 +107,13 @@ impl Camera {
         let viewport_width = viewport_height * (f64::from(image_width) / f64::from(image_height));
         let samples_per_pixel = samples_per_pixel.unwrap_or(100);
         let max_depth = max_depth.unwrap_or(10);
+        let environment = environment.unwrap_or_else(|| Arc::new(Gradient::default()));
+        let seed = seed.unwrap_or(0);
+        // Adaptive sampling needs a target to aim for.
+        let noise_threshold = noise_threshold.or(min_samples_per_pixel.map(|_| 0.01));
+        // With no shutter interval, everything is rendered frozen at the moment it opens.
+        let shutter_open = shutter_open.unwrap_or(0.);
+        let shutter_close = shutter_close.unwrap_or(shutter_open);
         Self {
             focus_dist,
             image_width,
@@ -80,6 +126,17 @@ impl Camera {
             defocus_disk_v,
             samples_per_pixel,
             max_depth,
+            environment,
+            seed,
+            sampler: sampler.unwrap_or_default(),
+            filter: Filter::new(filter.unwrap_or_default(), filter_radius),
+            tile_size: tile_size.unwrap_or(32),
+            tile_order: tile_order.unwrap_or_default(),
+            time_limit,
+            noise_threshold,
+            min_samples_per_pixel,
+            shutter_open,
+            shutter_close,
             camera_u,
             camera_v,
             camera_w,
@@ -112,32 +169,158 @@ impl Camera {
         viewport_upper_left + 0.5 * (self.viewport_delta_u() + self.viewport_delta_v())
     }
 
-    pub fn render<T: Hittable>(&self, world: &T) -> RgbImage {
-        let mut output = RgbImage::new(self.image_width, self.image_height);
+    /// Render the world into a linear floating point framebuffer.
+    #[allow(unused)]
+    pub fn render<T: Hittable>(&self, world: &T) -> Rgb32FImage {
+        let film = self
+            .render_progressive(world, self.new_film(), |_| Ok(()))
+            .expect("rendering without a callback can't fail");
 
-        output
-            .par_enumerate_pixels_mut()
-            .progress()
-            .for_each(|(x_pos, y_pos, pixel)| {
-                let x = f64::from(x_pos);
-                let y = f64::from(y_pos);
+        film.image()
+    }
 
-                let mut color_vector = Vector3::new(0., 0., 0.);
+    /// Create an empty film to render into, laid out for this camera.
+    pub fn new_film(&self) -> Film {
+        let tiles = tiles(
+            self.image_width,
+            self.image_height,
+            self.tile_size,
+            self.tile_order,
+        );
 
-                for _ in 0..self.samples_per_pixel {
-                    let ray = self.get_ray(x, y);
-                    color_vector += Self::ray_color(world, &ray, self.max_depth);
-                }
+        Film::new(self.image_width, self.image_height, self.filter, tiles)
+    }
 
-                #[allow(clippy::cast_precision_loss)]
-                color_vector.unscale_mut(self.samples_per_pixel as f64);
-                *pixel = vector_to_color(&color_vector);
-            });
+    /// Get the settings that decide which samples this camera gives its films.
+    pub fn sampling_settings(&self) -> SamplingSettings {
+        SamplingSettings {
+            seed: self.seed,
+            sampler: self.sampler,
+            samples_per_pixel: self.samples_per_pixel,
+        }
+    }
+
+    /// Get the number of tiles in this camera's films, without making one.
+    pub fn tile_count(&self) -> usize {
+        tiles(
+            self.image_width,
+            self.image_height,
+            self.tile_size,
+            self.tile_order,
+        )
+        .len()
+    }
+
+    /// Render the world into `film` one sample per pixel at a time, calling `on_pass` with
+    /// the film so far after each pass.
+    ///
+    /// The film can be one that already has passes in it, from [`Film::load_checkpoint`], and
+    /// rendering carries on from where it left off. Which random numbers each sample uses
+    /// only depends on the seed and how many samples its pixel already has, so the result is
+    /// the same as if the render had never stopped, apart from the time limit starting over.
+    ///
+    /// Rendering stops after `samples_per_pixel` passes, or earlier once the time limit has
+    /// passed or every pixel's estimated relative error is under the noise threshold. Both
+    /// are only checked between passes, so the time limit can be overrun by up to a pass.
+    ///
+    /// With adaptive sampling, each pixel stops getting samples as soon as it has had
+    /// `min_samples_per_pixel` of them and its own error is under the noise threshold, so the
+    /// remaining passes are spent only on the pixels that are still noisy.
+    ///
+    /// Each sample draws from its own random number stream, so the same camera and world
+    /// always produce the same image, however many threads do the work.
+    pub fn render_progressive<T: Hittable>(
+        &self,
+        world: &T,
+        mut film: Film,
+        mut on_pass: impl FnMut(&Film) -> Result<()>,
+    ) -> Result<Film> {
+        // The variance estimate is too unreliable to stop on before this many samples.
+        const MIN_SAMPLES_FOR_NOISE: usize = 16;
+
+        let min_samples = self.min_samples_per_pixel.unwrap_or(MIN_SAMPLES_FOR_NOISE);
+        let adaptive_threshold = self.min_samples_per_pixel.and(self.noise_threshold);
+
+        let start = Instant::now();
+        let progress = ProgressBar::new((self.samples_per_pixel * film.tile_count()) as u64);
+        progress.set_position((film.passes() * film.tile_count()) as u64);
+
+        while film.passes() < self.samples_per_pixel {
+            film.add_pass(
+                |x, y, pixel| {
+                    let converged = adaptive_threshold.is_some_and(|threshold| {
+                        pixel.samples() >= min_samples as u64 && pixel.relative_error() < threshold
+                    });
+
+                    (!converged).then(|| self.sample_pixel(world, x, y, pixel.samples()))
+                },
+                |_| progress.inc(1),
+            );
+
+            on_pass(&film)?;
+
+            let out_of_time = self
+                .time_limit
+                .is_some_and(|limit| start.elapsed() >= limit);
+            let converged = film.passes() >= min_samples
+                && self
+                    .noise_threshold
+                    .is_some_and(|threshold| film.max_relative_error() < threshold);
+            if out_of_time || converged {
+                break;
+            }
+        }
+
+        progress.finish();
+        Ok(film)
+    }
+
+    /// Trace the `sample`th sample of a pixel, using the camera's kind of sampler.
+    fn sample_pixel(&self, world: &impl Hittable, x: u32, y: u32, sample: u64) -> FilmSample {
+        let seed = self.seed;
+
+        match self.sampler {
+            SamplerKind::Independent => {
+                self.trace_sample(world, x, y, &mut Independent::new(seed, x, y, sample))
+            }
+            SamplerKind::Stratified => self.trace_sample(
+                world,
+                x,
+                y,
+                &mut Stratified::new(seed, x, y, sample, self.samples_per_pixel),
+            ),
+            SamplerKind::Halton => {
+                self.trace_sample(world, x, y, &mut Halton::new(seed, x, y, sample))
+            }
+            SamplerKind::Sobol => {
+                self.trace_sample(world, x, y, &mut Sobol::new(seed, x, y, sample))
+            }
+        }
+    }
 
-        output
+    fn trace_sample(
+        &self,
+        world: &impl Hittable,
+        x: u32,
+        y: u32,
+        sampler: &mut dyn Sampler,
+    ) -> FilmSample {
+        let offset = sampler.next_2d().map(|c| c - 0.5).coords;
+        let ray = self.get_ray(f64::from(x) + offset.x, f64::from(y) + offset.y, sampler);
+
+        FilmSample {
+            offset,
+            color: self.ray_color(world, &ray, self.max_depth, sampler),
+        }
     }
 
-    fn ray_color(world: &impl Hittable, ray: &Ray, max_depth: usize) -> Vector3<f64> {
+    fn ray_color(
+        &self,
+        world: &impl Hittable,
+        ray: &Ray,
+        max_depth: usize,
+        sampler: &mut dyn Sampler,
+    ) -> Vector3<f64> {
         if max_depth == 0 {
             return Vector3::new(0., 0., 0.);
         }
@@ -145,62 +328,51 @@ impl Camera {
         let intersection = world.hits(ray, Interval::new(0.001, f64::INFINITY));
         if let Some(hit) = &intersection {
             let Hit { material, .. } = &hit;
+            let emitted = material.emitted(ray, hit);
 
             return if let Some(Scattered {
                 attenuation,
                 scatter_ray,
-            }) = material.scatter(ray, hit)
+            }) = material.scatter(ray, hit, sampler)
             {
-                let color: Vector3<f64> = Self::ray_color(world, &scatter_ray, max_depth - 1);
-                attenuation.component_mul(&color)
+                let color: Vector3<f64> =
+                    self.ray_color(world, &scatter_ray, max_depth - 1, sampler);
+                emitted + attenuation.component_mul(&color)
             } else {
-                Vector3::new(0., 0., 0.)
+                emitted
             };
         }
 
-        let unit_direction = ray.direction().normalize();
-        let a = 0.5 * (unit_direction.y + 1.);
-
-        Vector3::new(1. - 0.5 * a, 1. - 0.3 * a, 1.)
+        self.environment.radiance(ray.direction())
     }
 
-    fn get_ray(&self, x: f64, y: f64) -> Ray {
-        let offset = sample_for_pixel();
-        let pixel_center = self.pixel_0_0_at()
-            + (x + offset.x) * self.viewport_delta_u()
-            + (y + offset.y) * self.viewport_delta_v();
+    /// Get a ray through the point `(x, y)` on the image, in pixels from the center of the
+    /// top left pixel.
+    fn get_ray(&self, x: f64, y: f64, sampler: &mut dyn Sampler) -> Ray {
+        let pixel_center =
+            self.pixel_0_0_at() + x * self.viewport_delta_u() + y * self.viewport_delta_v();
 
         let ray_origin = if self.defocus_angle <= 0. {
             self.look_from
         } else {
-            self.defocus_disk_sample()
+            self.defocus_disk_sample(sampler)
         };
         let ray_direction = pixel_center - ray_origin;
 
-        Ray::new(ray_origin, ray_direction)
+        Ray::new(ray_origin, ray_direction, self.shutter_time(sampler))
     }
 
-    fn defocus_disk_sample(&self) -> Point3<f64> {
-        let p = random_in_unit_disk();
-        self.look_from + (p.x * self.defocus_disk_u) + (p.y * self.defocus_disk_v)
+    /// Pick a time for a ray while the shutter is open.
+    fn shutter_time(&self, sampler: &mut dyn Sampler) -> f64 {
+        if self.shutter_close > self.shutter_open {
+            self.shutter_open + (self.shutter_close - self.shutter_open) * sampler.next_1d()
+        } else {
+            self.shutter_open
+        }
     }
-}
 
-fn sample_for_pixel() -> Vector3<f64> {
-    Vector3::new(rand::random::<f64>() - 0.5, rand::random::<f64>() - 0.5, 0.)
-}
-
-fn vector_to_color(vec: &Vector3<f64>) -> Rgb<u8> {
-    let valid_intensity = Interval::new(0., 0.999);
-
-    let r = vec.x.powf(1. / 2.2);
-    let g = vec.y.powf(1. / 2.2);
-    let b = vec.z.powf(1. / 2.2);
-
-    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
-    Rgb([
-        (256. * valid_intensity.clamp(r)) as u8,
-        (256. * valid_intensity.clamp(g)) as u8,
-        (256. * valid_intensity.clamp(b)) as u8,
-    ])
+    fn defocus_disk_sample(&self, sampler: &mut dyn Sampler) -> Point3<f64> {
+        let p = sample_unit_disk(sampler.next_2d());
+        self.look_from + (p.x * self.defocus_disk_u) + (p.y * self.defocus_disk_v)
+    }
 }
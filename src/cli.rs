@@ -0,0 +1,268 @@
+use std::num::NonZeroUsize;
+use std::ops::Range;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail, Context, Error, Result};
+use clap::Parser;
+
+use crate::filter::FilterKind;
+use crate::output::OutputFormat;
+use crate::samplers::SamplerKind;
+use crate::tiles::TileOrder;
+use crate::tonemap::ToneMapping;
+
+/// Everybody loves raytracing.
+#[derive(Parser, Debug)]
+#[command(version, about)]
+pub struct Args {
+    /// The scene file to render. Renders the built-in sphere field animation if omitted.
+    #[arg(short, long)]
+    pub scene: Option<PathBuf>,
+
+    /// The directory rendered frames are written to.
+    #[arg(short, long, default_value = "./output")]
+    pub output: PathBuf,
+
+    /// The file format rendered frames are saved in.
+    #[arg(long, value_enum, default_value_t)]
+    pub format: OutputFormat,
+
+    /// Also encode the rendered frames into a video at this path, as they're rendered. GIFs
+    /// and animated PNGs (`.png`) are written directly, and other formats are encoded by
+    /// `ffmpeg`, which has to be installed.
+    #[arg(long, value_name = "FILE")]
+    pub video: Option<PathBuf>,
+
+    /// The frame rate of the video, in frames per second.
+    #[arg(long, default_value_t = 30., value_parser = parse_frame_rate)]
+    pub frame_rate: f64,
+
+    /// The codec `ffmpeg` encodes the video with, e.g. `libx264`. Defaults to whatever
+    /// `ffmpeg` picks for the video's file extension.
+    #[arg(long)]
+    pub codec: Option<String>,
+
+    /// The output resolution, as `WIDTHxHEIGHT`.
+    #[arg(short, long)]
+    pub resolution: Option<Resolution>,
+
+    /// The tone mapping curve used for 8-bit output.
+    #[arg(long, value_enum)]
+    pub tone_mapping: Option<ToneMapping>,
+
+    /// The exposure adjustment for 8-bit output, in stops.
+    #[arg(long, allow_negative_numbers = true)]
+    pub exposure: Option<f64>,
+
+    /// The luminance that becomes pure white with extended Reinhard tone mapping.
+    #[arg(long, value_parser = parse_white_point)]
+    pub white_point: Option<f64>,
+
+    /// The number of samples taken for each pixel, or the most taken with adaptive sampling.
+    #[arg(long)]
+    pub samples: Option<usize>,
+
+    /// How the samples for each pixel are spread out.
+    #[arg(long, value_enum)]
+    pub sampler: Option<SamplerKind>,
+
+    /// The filter samples are reconstructed into pixels with.
+    #[arg(long, value_enum)]
+    pub filter: Option<FilterKind>,
+
+    /// How far the reconstruction filter reaches from each sample, in pixels. Each filter
+    /// has its own default.
+    #[arg(long, value_name = "PIXELS", value_parser = parse_filter_radius)]
+    pub filter_radius: Option<f64>,
+
+    /// The width and height of the tiles the image is rendered in, in pixels.
+    #[arg(long)]
+    pub tile_size: Option<u32>,
+
+    /// The order the tiles are rendered in.
+    #[arg(long, value_enum)]
+    pub tile_order: Option<TileOrder>,
+
+    /// Sample adaptively, giving every pixel at least this many samples and then only
+    /// sampling the pixels still above the noise threshold (1% by default).
+    #[arg(long)]
+    pub min_samples: Option<usize>,
+
+    /// Stop adding samples to a frame after this many seconds.
+    #[arg(long, value_name = "SECONDS", value_parser = parse_seconds)]
+    pub time_limit: Option<f64>,
+
+    /// Stop adding samples to a frame once every pixel's estimated relative error is below
+    /// this, e.g. 0.01 for 1%.
+    #[arg(long)]
+    pub noise_threshold: Option<f64>,
+
+    /// Save the frame being rendered every N samples per pixel, to check on its progress.
+    #[arg(long, value_name = "N")]
+    pub preview_every: Option<NonZeroUsize>,
+
+    /// The time the shutter opens, for motion blur.
+    #[arg(long, allow_negative_numbers = true)]
+    pub shutter_open: Option<f64>,
+
+    /// The time the shutter closes. Moving objects are blurred over the time the shutter is
+    /// open, and frozen at the moment it opens if it closes straight away.
+    #[arg(long, allow_negative_numbers = true)]
+    pub shutter_close: Option<f64>,
+
+    /// The maximum number of times a ray may bounce.
+    #[arg(long)]
+    pub max_depth: Option<usize>,
+
+    /// The number of threads to render with. Defaults to one per logical CPU.
+    #[arg(short = 'j', long)]
+    pub threads: Option<usize>,
+
+    /// The frames to render, as a single frame number `N` or an inclusive range `FIRST-LAST`.
+    /// Frames are numbered from 1.
+    #[arg(short, long)]
+    pub frames: Option<FrameRange>,
+
+    /// Carry on from an earlier run that was cut short, skipping the frames it finished and
+    /// resuming the one it was in the middle of from its checkpoint.
+    #[arg(long)]
+    pub resume: bool,
+
+    /// How often to checkpoint the frame being rendered, in seconds.
+    #[arg(long, value_name = "SECONDS", default_value_t = 60., value_parser = parse_seconds)]
+    pub checkpoint_interval: f64,
+
+    /// Coordinate a render across worker processes instead of rendering here, listening for
+    /// workers on this address, e.g. `0.0.0.0:7878`.
+    ///
+    /// Frames are split into work units of a few tiles each, and the time limit and noise
+    /// threshold apply to each work unit on its own rather than to the whole frame.
+    #[arg(long, value_name = "ADDRESS", conflicts_with = "worker")]
+    pub coordinator: Option<String>,
+
+    /// How long the coordinator waits for a worker to send back a work unit before giving up
+    /// on it and handing the unit to someone else, in seconds.
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        default_value_t = 600.,
+        value_parser = parse_seconds,
+        requires = "coordinator"
+    )]
+    pub worker_timeout: f64,
+
+    /// Render for the coordinator at this address instead of on our own. Workers need to be
+    /// given the same scene file as the coordinator, and get every other setting from it.
+    #[arg(long, value_name = "ADDRESS")]
+    pub worker: Option<String>,
+
+    /// The seed for rendering, and for generating the built-in scene. Renders with the same
+    /// seed come out identical.
+    #[arg(long)]
+    pub seed: Option<u64>,
+}
+
+fn parse_seconds(s: &str) -> Result<f64> {
+    let seconds: f64 = s.trim().parse().context("invalid number of seconds")?;
+    if !(seconds > 0. && seconds.is_finite()) {
+        bail!("expected a positive number of seconds");
+    }
+
+    Ok(seconds)
+}
+
+fn parse_filter_radius(s: &str) -> Result<f64> {
+    let radius: f64 = s.trim().parse().context("invalid filter radius")?;
+    if !(radius > 0. && radius.is_finite()) {
+        bail!("expected a positive number of pixels");
+    }
+
+    Ok(radius)
+}
+
+fn parse_white_point(s: &str) -> Result<f64> {
+    let white_point: f64 = s.trim().parse().context("invalid white point")?;
+    if !(white_point > 0. && white_point.is_finite()) {
+        bail!("expected a positive luminance");
+    }
+
+    Ok(white_point)
+}
+
+fn parse_frame_rate(s: &str) -> Result<f64> {
+    let frame_rate: f64 = s.trim().parse().context("invalid frame rate")?;
+    if !(frame_rate > 0. && frame_rate.is_finite()) {
+        bail!("expected a positive frame rate");
+    }
+
+    Ok(frame_rate)
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct Resolution {
+    pub width: u32,
+    pub height: u32,
+}
+
+impl FromStr for Resolution {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (width, height) = s
+            .split_once(['x', 'X'])
+            .ok_or_else(|| anyhow!("expected a resolution like `1920x1080`"))?;
+        let width = width.trim().parse().context("invalid width")?;
+        let height = height.trim().parse().context("invalid height")?;
+
+        if width == 0 || height == 0 {
+            bail!("resolution must be nonzero in both dimensions");
+        }
+
+        Ok(Self { width, height })
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct FrameRange {
+    pub first: u32,
+    pub last: u32,
+}
+
+impl FrameRange {
+    /// Limit the range to the frames that exist in an animation `frames` long, failing if
+    /// none of them do.
+    pub fn clamp_to(&self, frames: u32) -> Result<Range<u32>> {
+        if self.first > frames {
+            bail!("the animation only has {frames} frames");
+        }
+
+        Ok(self.first..self.last.min(frames) + 1)
+    }
+}
+
+impl FromStr for FrameRange {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (first, last) = match s.split_once('-') {
+            Some((first, last)) => (
+                first.trim().parse().context("invalid first frame")?,
+                last.trim().parse().context("invalid last frame")?,
+            ),
+            None => {
+                let frame = s.trim().parse().context("invalid frame number")?;
+                (frame, frame)
+            }
+        };
+
+        if first == 0 {
+            bail!("frames are numbered from 1");
+        }
+        if first > last {
+            bail!("the first frame must not come after the last frame");
+        }
+
+        Ok(Self { first, last })
+    }
+}
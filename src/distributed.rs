@@ -0,0 +1,13 @@
+//! Splitting a render across worker processes, possibly on other machines, over TCP.
+//!
+//! The coordinator listens for workers and hands each of them one unit of work at a time,
+//! made up of some of the tiles of one frame. Workers load the same scene as the coordinator
+//! from their own command line, render the tiles they're given into a film and send it
+//! back, and the coordinator merges the films into whole frames.
+
+mod coordinator;
+mod protocol;
+mod worker;
+
+pub use coordinator::coordinate;
+pub use worker::work;
@@ -0,0 +1,265 @@
+use std::collections::{HashMap, VecDeque};
+use std::mem;
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use indicatif::ProgressBar;
+
+use crate::camera::Camera;
+use crate::distributed::protocol::{
+    receive, send, CoordinatorMessage, WorkUnit, WorkerMessage, PROTOCOL_VERSION,
+};
+use crate::film::Film;
+use crate::scene::{CameraSettings, Scene};
+
+/// How many tiles go into each work unit.
+const TILES_PER_UNIT: usize = 16;
+/// How many frames can be queued up at once. Each one needs a whole film to gather its tiles
+/// in, so a long animation can't have all of its frames going at the same time.
+const FRAMES_IN_FLIGHT: usize = 3;
+
+/// The work that hasn't been handed out yet, shared between the connections to workers.
+struct Queue {
+    units: VecDeque<WorkUnit>,
+    /// The number of units that haven't been finished, including those being rendered.
+    unfinished: usize,
+    /// Whether every frame's units have been queued, so that there's nothing more to wait
+    /// for once they're finished.
+    all_queued: bool,
+    /// The id to give the next unit that's queued.
+    next_id: usize,
+}
+
+impl Queue {
+    /// Split a frame into work units and add them to the back of the queue, returning how
+    /// many there are.
+    fn push_frame(&mut self, frame: u32, settings: &CameraSettings, tile_count: usize) -> usize {
+        let tiles: Vec<usize> = (0..tile_count).collect();
+
+        for chunk in tiles.chunks(TILES_PER_UNIT) {
+            self.units.push_back(WorkUnit {
+                id: self.next_id,
+                frame,
+                camera: settings.clone(),
+                tiles: chunk.to_vec(),
+            });
+            self.next_id += 1;
+        }
+
+        let units = tile_count.div_ceil(TILES_PER_UNIT);
+        self.unfinished += units;
+        units
+    }
+}
+
+/// A frame whose units have been queued, but haven't all come back yet.
+struct FrameInFlight {
+    camera: Camera,
+    /// The film the frame's tiles are merged into, which is only made once the first of
+    /// them comes back.
+    film: Option<Film>,
+    /// The number of the frame's units that haven't come back yet.
+    remaining: usize,
+}
+
+/// Render `frames` of the scene on whichever workers connect to `address`, calling
+/// `on_frame` with each frame's film as soon as all of its tiles are back.
+///
+/// Work units are handed out in order, so frames tend to finish in order too. If a worker
+/// disconnects partway through a unit, or doesn't send it back within `worker_timeout`, the
+/// unit goes back in the queue for someone else.
+///
+/// Each unit is rendered on its own, so the camera's time limit and noise threshold apply
+/// to each unit rather than to the whole frame.
+///
+/// Only [`FRAMES_IN_FLIGHT`] frames are queued at a time, and the next one is only queued
+/// once one of those is finished and handed to `on_frame`.
+pub fn coordinate(
+    address: &str,
+    scene: &Scene,
+    seed: u64,
+    worker_timeout: Duration,
+    frames: &[u32],
+    mut on_frame: impl FnMut(u32, Film) -> Result<()>,
+) -> Result<()> {
+    let progress = ProgressBar::new(0);
+    let queue = Arc::new(Mutex::new(Queue {
+        units: VecDeque::new(),
+        unfinished: 0,
+        all_queued: false,
+        next_id: 0,
+    }));
+    let mut upcoming = frames.iter().copied();
+    let mut in_flight = HashMap::new();
+    let (results, rendered) = mpsc::channel();
+
+    let listener = TcpListener::bind(address)
+        .with_context(|| format!("couldn't listen for workers on {address}"))?;
+    println!("Waiting for workers on {}", listener.local_addr()?);
+    let connections = Arc::new(Mutex::new(Vec::new()));
+    let worker_progress = progress.clone();
+    let worker_connections = Arc::clone(&connections);
+    let worker_queue = Arc::clone(&queue);
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let queue = Arc::clone(&worker_queue);
+            let results = results.clone();
+            let progress = worker_progress.clone();
+
+            let connection = thread::spawn(move || {
+                let peer = stream.peer_addr().map(|peer| peer.to_string());
+                if let Err(error) = serve_worker(stream, seed, worker_timeout, &queue, &results) {
+                    let peer = peer.unwrap_or_else(|_| "a worker".to_string());
+                    progress.suspend(|| eprintln!("Lost {peer}: {error:#}"));
+                }
+            });
+            worker_connections
+                .lock()
+                .expect("the connections' lock was poisoned")
+                .push(connection);
+        }
+    });
+
+    loop {
+        while in_flight.len() < FRAMES_IN_FLIGHT {
+            let Some(frame) = upcoming.next() else {
+                queue
+                    .lock()
+                    .expect("the work queue's lock was poisoned")
+                    .all_queued = true;
+                break;
+            };
+
+            let settings = scene.camera_for_frame(frame)?;
+            let camera = settings.build();
+            let units = queue
+                .lock()
+                .expect("the work queue's lock was poisoned")
+                .push_frame(frame, &settings, camera.tile_count());
+            progress.inc_length(units as u64);
+
+            in_flight.insert(
+                frame,
+                FrameInFlight {
+                    camera,
+                    film: None,
+                    remaining: units,
+                },
+            );
+        }
+
+        if in_flight.is_empty() {
+            break;
+        }
+
+        let (frame, film): (u32, Film) = rendered.recv()?;
+        progress.inc(1);
+
+        let frame_in_flight: &mut FrameInFlight = in_flight
+            .get_mut(&frame)
+            .expect("workers are only given frames that are being rendered");
+        frame_in_flight
+            .film
+            .get_or_insert_with(|| frame_in_flight.camera.new_film())
+            .merge(film)?;
+        frame_in_flight.remaining -= 1;
+
+        if frame_in_flight.remaining == 0 {
+            let film = in_flight
+                .remove(&frame)
+                .and_then(|frame_in_flight| frame_in_flight.film)
+                .expect("the frame was just found, with a tile merged into its film");
+            on_frame(frame, film)?;
+        }
+    }
+
+    progress.finish();
+
+    // Give the workers the chance to hear that they're done before we exit.
+    let connections = mem::take(
+        &mut *connections
+            .lock()
+            .expect("the connections' lock was poisoned"),
+    );
+    for connection in connections {
+        let _ = connection.join();
+    }
+
+    Ok(())
+}
+
+/// Hand out work units to one worker until there are none left.
+///
+/// A worker that takes longer than `timeout` to send back a unit, or to take in a message,
+/// is given up on, and the unit it had goes back in the queue.
+fn serve_worker(
+    mut stream: TcpStream,
+    seed: u64,
+    timeout: Duration,
+    queue: &Mutex<Queue>,
+    results: &Sender<(u32, Film)>,
+) -> Result<()> {
+    stream.set_read_timeout(Some(timeout))?;
+    stream.set_write_timeout(Some(timeout))?;
+
+    send(
+        &mut stream,
+        &CoordinatorMessage::Hello {
+            version: PROTOCOL_VERSION,
+            seed,
+        },
+    )?;
+
+    loop {
+        let next = {
+            let mut queue = queue.lock().expect("the work queue's lock was poisoned");
+            match queue.units.pop_front() {
+                Some(unit) => Some(unit),
+                None if queue.all_queued && queue.unfinished == 0 => break,
+                None => None,
+            }
+        };
+
+        // Everything's been handed out, but it isn't all done yet, and any of it could
+        // still come back if its worker goes away. There might be more frames still to
+        // come, too, once the ones being rendered are finished.
+        let Some(unit) = next else {
+            thread::sleep(Duration::from_millis(100));
+            continue;
+        };
+
+        match render_unit(&mut stream, &unit) {
+            Ok(film) => {
+                queue
+                    .lock()
+                    .expect("the work queue's lock was poisoned")
+                    .unfinished -= 1;
+                // The coordinator only stops listening once every unit is in.
+                let _ = results.send((unit.frame, film));
+            }
+            Err(error) => {
+                let mut queue = queue.lock().expect("the work queue's lock was poisoned");
+                queue.units.push_front(unit);
+                return Err(error);
+            }
+        }
+    }
+
+    send(&mut stream, &CoordinatorMessage::Done)
+}
+
+fn render_unit(stream: &mut TcpStream, unit: &WorkUnit) -> Result<Film> {
+    send(stream, &CoordinatorMessage::Render(Box::new(unit.clone())))?;
+
+    let WorkerMessage::Rendered { id, film } =
+        receive(stream).context("the worker didn't send back its work unit")?;
+    if id != unit.id {
+        bail!("the worker sent back work unit {id} instead of {}", unit.id);
+    }
+
+    Ok(film)
+}
@@ -0,0 +1,68 @@
+use std::io::{Read, Write};
+
+use anyhow::{bail, Result};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::film::Film;
+use crate::scene::CameraSettings;
+
+/// Bumped whenever the messages change, so mismatched builds refuse to talk to each other.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// The largest message either side will accept, to fail cleanly on a garbled length.
+const MAX_MESSAGE_LEN: u64 = 1 << 32;
+
+/// Some of the tiles of one frame, to be rendered by a worker.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WorkUnit {
+    pub id: usize,
+    pub frame: u32,
+    pub camera: CameraSettings,
+    /// Indices into the tiles of the camera's film.
+    pub tiles: Vec<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub enum CoordinatorMessage {
+    /// The first message on every connection.
+    Hello {
+        version: u32,
+        /// The seed the coordinator generated the built-in scene with, if it's using it.
+        seed: u64,
+    },
+    Render(Box<WorkUnit>),
+    /// There's nothing left to render, and the worker can exit.
+    Done,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub enum WorkerMessage {
+    /// The film for the work unit with the given ID, with only that unit's tiles in it.
+    Rendered { id: usize, film: Film },
+}
+
+/// Send a message, as its length in bytes followed by its bincode encoding.
+pub fn send(stream: &mut impl Write, message: &impl Serialize) -> Result<()> {
+    let bytes = bincode::serialize(message)?;
+
+    stream.write_all(&(bytes.len() as u64).to_le_bytes())?;
+    stream.write_all(&bytes)?;
+    stream.flush()?;
+    Ok(())
+}
+
+/// Receive a message sent with [`send`].
+pub fn receive<T: DeserializeOwned>(stream: &mut impl Read) -> Result<T> {
+    let mut len = [0; 8];
+    stream.read_exact(&mut len)?;
+    let len = u64::from_le_bytes(len);
+    if len > MAX_MESSAGE_LEN {
+        bail!("received a message claiming to be {len} bytes long");
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    let mut bytes = vec![0; len as usize];
+    stream.read_exact(&mut bytes)?;
+    Ok(bincode::deserialize(&bytes)?)
+}
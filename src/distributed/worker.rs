@@ -0,0 +1,114 @@
+use std::net::TcpStream;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+
+use crate::distributed::protocol::{
+    receive, send, CoordinatorMessage, WorkerMessage, PROTOCOL_VERSION,
+};
+use crate::environments::Environment;
+use crate::geometry::bvh::Bvh;
+use crate::scene::Scene;
+
+/// The world for one frame of a scene whose animation changes it.
+struct FrameBvh {
+    frame: u32,
+    world: Bvh,
+    environment: Option<Arc<dyn Environment>>,
+}
+
+/// How many times to try reaching the coordinator, a second apart, before giving up.
+const CONNECT_ATTEMPTS: u32 = 30;
+/// How long sending a rendered work unit back to the coordinator can take before we give up
+/// on the coordinator.
+///
+/// There's no limit on reading, since the coordinator can go quiet for as long as it likes
+/// while it waits for other workers to finish the frames it has going.
+const WRITE_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Render work units for the coordinator at `address` until it runs out of them.
+///
+/// The scene is loaded with `load_scene` once the coordinator has said which seed it's
+/// using, and has to be the same scene the coordinator is rendering.
+pub fn work(address: &str, load_scene: impl FnOnce(u64) -> Result<Scene>) -> Result<()> {
+    let mut stream = connect(address)?;
+    stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+
+    let seed = match receive(&mut stream)? {
+        CoordinatorMessage::Hello { version, seed } if version == PROTOCOL_VERSION => seed,
+        CoordinatorMessage::Hello { version, .. } => bail!(
+            "the coordinator speaks protocol version {version}, but this worker speaks \
+             version {PROTOCOL_VERSION}"
+        ),
+        message => bail!("expected a greeting from the coordinator, got {message:?}"),
+    };
+
+    let mut scene = load_scene(seed)?;
+    let world = Bvh::new(std::mem::take(&mut scene.world));
+    // The world for the frame being rendered, if the scene's animation changes it.
+    let mut frame_world: Option<FrameBvh> = None;
+    println!("Connected to the coordinator at {address}");
+
+    loop {
+        let unit = match receive(&mut stream)? {
+            CoordinatorMessage::Render(unit) => *unit,
+            CoordinatorMessage::Done => return Ok(()),
+            message => bail!("unexpected message from the coordinator: {message:?}"),
+        };
+
+        if frame_world
+            .as_ref()
+            .is_none_or(|world| world.frame != unit.frame)
+        {
+            frame_world = scene
+                .world_for_frame(unit.frame)?
+                .map(|frame_world| FrameBvh {
+                    frame: unit.frame,
+                    world: Bvh::new(frame_world.world),
+                    environment: frame_world.environment,
+                });
+        }
+
+        // The environment can't be sent over the wire, so it comes from our own copy of the
+        // scene instead.
+        let mut settings = unit.camera;
+        let world = match &frame_world {
+            Some(frame_world) => {
+                settings.environment.clone_from(&frame_world.environment);
+                &frame_world.world
+            }
+            None => {
+                settings.environment.clone_from(&scene.camera.environment);
+                &world
+            }
+        };
+        let camera = settings.build();
+
+        let film = camera.new_film().select_tiles(&unit.tiles);
+        let film = camera
+            .render_progressive(world, film, |_| Ok(()))
+            .with_context(|| format!("couldn't render work unit {}", unit.id))?;
+
+        send(&mut stream, &WorkerMessage::Rendered { id: unit.id, film })?;
+    }
+}
+
+fn connect(address: &str) -> Result<TcpStream> {
+    let mut attempt = 1;
+
+    loop {
+        match TcpStream::connect(address) {
+            Ok(stream) => return Ok(stream),
+            Err(_) if attempt < CONNECT_ATTEMPTS => {
+                attempt += 1;
+                thread::sleep(Duration::from_secs(1));
+            }
+            Err(error) => {
+                return Err(error)
+                    .with_context(|| format!("couldn't connect to the coordinator at {address}"))
+            }
+        }
+    }
+}
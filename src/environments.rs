@@ -0,0 +1,13 @@
+mod environment;
+mod environment_map;
+mod gradient;
+mod solid_color;
+
+#[allow(unused_imports)]
+pub use environment::*;
+#[allow(unused_imports)]
+pub use environment_map::*;
+#[allow(unused_imports)]
+pub use gradient::*;
+#[allow(unused_imports)]
+pub use solid_color::*;
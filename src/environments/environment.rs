@@ -0,0 +1,9 @@
+use std::fmt::Debug;
+
+use na::Vector3;
+
+/// The light arriving from infinitely far away, for rays that don't hit anything.
+pub trait Environment: Send + Sync + Debug {
+    /// Get the radiance arriving from the given direction, which need not be normalized.
+    fn radiance(&self, direction: &Vector3<f64>) -> Vector3<f64>;
+}
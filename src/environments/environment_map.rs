@@ -0,0 +1,110 @@
+use std::f64::consts::{PI, TAU};
+use std::fmt;
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::{bail, Context, Result};
+use image::Rgb32FImage;
+use na::Vector3;
+
+use crate::environments::Environment;
+
+/// An environment lit by an equirectangular (latitude-longitude) image, typically a
+/// high dynamic range `.hdr` or `.exr` file.
+pub struct EnvironmentMap {
+    /// The image, which is shared between maps that only differ in how they're oriented or
+    /// how bright they are.
+    image: Arc<Rgb32FImage>,
+    /// The rotation around the vertical axis, in radians.
+    rotation: f64,
+    intensity: f64,
+}
+
+impl EnvironmentMap {
+    /// Load an environment map from an image file.
+    ///
+    /// `rotation` turns the map around the vertical axis, in degrees, and `intensity` scales
+    /// the radiance it gives off.
+    #[allow(unused)]
+    pub fn load(path: &Path, rotation: f64, intensity: f64) -> Result<Self> {
+        Ok(Self::new(Self::load_image(path)?, rotation, intensity))
+    }
+
+    /// Load just the image for an environment map, to make any number of maps out of.
+    pub fn load_image(path: &Path) -> Result<Rgb32FImage> {
+        let image = image::open(path)
+            .with_context(|| format!("couldn't load environment map {}", path.display()))?
+            .into_rgb32f();
+        if image.width() == 0 || image.height() == 0 {
+            bail!(
+                "the environment map {} is empty, at {}x{}",
+                path.display(),
+                image.width(),
+                image.height()
+            );
+        }
+
+        Ok(image)
+    }
+
+    #[allow(unused)]
+    pub fn new(image: impl Into<Arc<Rgb32FImage>>, rotation: f64, intensity: f64) -> Self {
+        EnvironmentMap {
+            image: image.into(),
+            rotation: rotation.to_radians(),
+            intensity,
+        }
+    }
+
+    /// Bilinearly sample the image at texture coordinates `(u, v)`, where `u` wraps around
+    /// horizontally and `v` runs from the top edge to the bottom.
+    fn sample(&self, u: f64, v: f64) -> Vector3<f64> {
+        let width = self.image.width();
+        let height = self.image.height();
+
+        let x = u * f64::from(width) - 0.5;
+        let y = (v * f64::from(height) - 0.5).clamp(0., f64::from(height - 1));
+        let x0 = x.floor();
+        let y0 = y.floor();
+        let tx = x - x0;
+        let ty = y - y0;
+
+        #[allow(clippy::cast_possible_truncation)]
+        let texel = |x: f64, y: f64| {
+            let x = (x as i64).rem_euclid(i64::from(width));
+            let y = (y as i64).clamp(0, i64::from(height) - 1);
+            #[allow(clippy::cast_sign_loss)]
+            let [r, g, b] = self.image.get_pixel(x as u32, y as u32).0;
+            Vector3::new(f64::from(r), f64::from(g), f64::from(b))
+        };
+
+        let top = texel(x0, y0).lerp(&texel(x0 + 1., y0), tx);
+        let bottom = texel(x0, y0 + 1.).lerp(&texel(x0 + 1., y0 + 1.), tx);
+        top.lerp(&bottom, ty)
+    }
+}
+
+impl fmt::Debug for EnvironmentMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EnvironmentMap")
+            .field("dimensions", &self.image.dimensions())
+            .field("rotation", &self.rotation)
+            .field("intensity", &self.intensity)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Environment for EnvironmentMap {
+    fn radiance(&self, direction: &Vector3<f64>) -> Vector3<f64> {
+        let unit_direction = direction.normalize();
+
+        // Looking down -z sees the middle of the map, with u increasing to the right.
+        let phi = f64::atan2(unit_direction.x, -unit_direction.z) - self.rotation;
+        let theta = unit_direction.y.clamp(-1., 1.).acos();
+
+        let u = (0.5 + phi / TAU).rem_euclid(1.);
+        let v = theta / PI;
+
+        self.intensity * self.sample(u, v)
+    }
+}
@@ -0,0 +1,35 @@
+use na::Vector3;
+
+use crate::environments::Environment;
+use crate::util::color;
+
+/// An environment that blends vertically between two colors, from straight down to
+/// straight up.
+#[derive(Copy, Clone, Debug)]
+pub struct Gradient {
+    bottom: Vector3<f64>,
+    top: Vector3<f64>,
+}
+
+impl Gradient {
+    #[allow(unused)]
+    pub fn new(bottom: Vector3<f64>, top: Vector3<f64>) -> Self {
+        Gradient { bottom, top }
+    }
+}
+
+impl Default for Gradient {
+    /// The white to sky blue gradient from _Ray Tracing in One Weekend_.
+    fn default() -> Self {
+        Self::new(color(1., 1., 1.), color(0.5, 0.7, 1.))
+    }
+}
+
+impl Environment for Gradient {
+    fn radiance(&self, direction: &Vector3<f64>) -> Vector3<f64> {
+        let unit_direction = direction.normalize();
+        let a = 0.5 * (unit_direction.y + 1.);
+
+        self.bottom.lerp(&self.top, a)
+    }
+}
@@ -0,0 +1,22 @@
+use na::Vector3;
+
+use crate::environments::Environment;
+
+/// An environment giving off the same color in every direction.
+#[derive(Copy, Clone, Debug)]
+pub struct SolidColor {
+    color: Vector3<f64>,
+}
+
+impl SolidColor {
+    #[allow(unused)]
+    pub fn new(color: Vector3<f64>) -> Self {
+        SolidColor { color }
+    }
+}
+
+impl Environment for SolidColor {
+    fn radiance(&self, _direction: &Vector3<f64>) -> Vector3<f64> {
+        self.color
+    }
+}
@@ -0,0 +1,367 @@
+//! The accumulation buffer that samples are added to as a render progresses.
+
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use image::{Rgb, Rgb32FImage};
+use na::{Point2, Vector2, Vector3};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::filter::Filter;
+use crate::samplers::SamplerKind;
+use crate::tiles::Tile;
+use crate::tonemap::luminance;
+
+/// The running totals for the samples taken for one pixel.
+///
+/// These only cover the pixel's own samples, and not the ones its neighbors splat into it
+/// through the reconstruction filter, so they describe how well the pixel has been sampled.
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize)]
+pub struct FilmPixel {
+    /// The sum of the samples' luminance and of its square, for estimating the variance.
+    luminance_sum: f64,
+    luminance_squared_sum: f64,
+    samples: u64,
+}
+
+impl FilmPixel {
+    fn add_sample(&mut self, color: Vector3<f64>) {
+        let l = luminance(&color);
+
+        self.luminance_sum += l;
+        self.luminance_squared_sum += l * l;
+        self.samples += 1;
+    }
+
+    pub fn samples(&self) -> u64 {
+        self.samples
+    }
+
+    /// The estimated standard error of the pixel's mean luminance, relative to that mean.
+    ///
+    /// Dark pixels are judged against a floor rather than their own tiny means, so that
+    /// they aren't held to a stricter standard than anyone could see.
+    pub fn relative_error(&self) -> f64 {
+        if self.samples < 2 {
+            return f64::INFINITY;
+        }
+
+        #[allow(clippy::cast_precision_loss)]
+        let n = self.samples as f64;
+        let mean = self.luminance_sum / n;
+        let variance = ((self.luminance_squared_sum - n * mean * mean) / (n - 1.)).max(0.);
+
+        (variance / n).sqrt() / mean.max(1e-2)
+    }
+}
+
+/// The settings that decide which samples a film is given, which are saved with its
+/// checkpoints so that a render is only ever carried on with the same ones.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SamplingSettings {
+    pub seed: u64,
+    pub sampler: SamplerKind,
+    pub samples_per_pixel: usize,
+}
+
+/// One sample taken for a pixel.
+pub struct FilmSample {
+    /// Where the sample was taken, relative to the center of the pixel.
+    pub offset: Vector2<f64>,
+    pub color: Vector3<f64>,
+}
+
+/// The filtered contributions splatted into a pixel by the samples around it.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+struct Splat {
+    weighted_sum: Vector3<f64>,
+    weight: f64,
+}
+
+impl Default for Splat {
+    fn default() -> Self {
+        Self {
+            weighted_sum: Vector3::zeros(),
+            weight: 0.,
+        }
+    }
+}
+
+/// A floating point framebuffer that accumulates samples over any number of passes.
+///
+/// Every sample is spread over the pixels within the reconstruction filter's radius,
+/// weighted by the filter, and each pixel's color is the weighted average of everything
+/// that landed on it.
+///
+/// A film can also hold just some of an image's tiles, for rendering them separately and
+/// putting them back together with [`Film::merge`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Film {
+    width: u32,
+    height: u32,
+    filter: Filter,
+    /// The number of passes taken so far.
+    passes: usize,
+    /// The tiles in the order they're rendered.
+    tiles: Vec<FilmTile>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct FilmTile {
+    tile: Tile,
+    /// The tile's pixels, row by row.
+    pixels: Vec<FilmPixel>,
+    /// The splats from the tile's samples, covering the tile and as far around it as the
+    /// filter reaches, row by row.
+    splats: Vec<Splat>,
+    splat_x: u32,
+    splat_y: u32,
+    splat_width: u32,
+}
+
+impl Film {
+    /// Create an empty film, which will be rendered in the given tiles in the given order.
+    pub fn new(width: u32, height: u32, filter: Filter, tiles: Vec<Tile>) -> Self {
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let margin = filter.radius().ceil() as u32;
+
+        Self {
+            width,
+            height,
+            filter,
+            passes: 0,
+            tiles: tiles
+                .into_iter()
+                .map(|tile| {
+                    let splat_x = tile.x.saturating_sub(margin);
+                    let splat_y = tile.y.saturating_sub(margin);
+                    let splat_width = (tile.x + tile.width + margin).min(width) - splat_x;
+                    let splat_height = (tile.y + tile.height + margin).min(height) - splat_y;
+
+                    FilmTile {
+                        tile,
+                        pixels: vec![FilmPixel::default(); tile.len()],
+                        splats: vec![
+                            Splat::default();
+                            splat_width as usize * splat_height as usize
+                        ],
+                        splat_x,
+                        splat_y,
+                        splat_width,
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    /// Take up to one sample for every pixel, by calling `sample` with each pixel's
+    /// coordinates and totals so far. Pixels it returns `None` for are skipped.
+    ///
+    /// The tiles are handed out to rayon's workers in order, and `on_tile` is called as each
+    /// one finishes. Each tile splats into its own buffer, which keeps the result the same
+    /// no matter how the tiles were spread across threads.
+    pub fn add_pass(
+        &mut self,
+        sample: impl Fn(u32, u32, &FilmPixel) -> Option<FilmSample> + Sync,
+        on_tile: impl Fn(&Tile) + Sync,
+    ) {
+        let width = self.width as usize;
+        let height = self.height as usize;
+        let filter = self.filter;
+
+        self.tiles.iter_mut().par_bridge().for_each(|film_tile| {
+            let tile = film_tile.tile;
+            let splat_x = film_tile.splat_x as usize;
+            let splat_y = film_tile.splat_y as usize;
+            let splat_width = film_tile.splat_width as usize;
+
+            for (i, pixel) in film_tile.pixels.iter_mut().enumerate() {
+                let x = tile.x + i as u32 % tile.width;
+                let y = tile.y + i as u32 / tile.width;
+                let Some(FilmSample { offset, color }) = sample(x, y, pixel) else {
+                    continue;
+                };
+                pixel.add_sample(color);
+
+                let position = Point2::new(f64::from(x), f64::from(y)) + offset;
+                let columns = pixel_range(position.x, filter.radius(), width);
+                let rows = pixel_range(position.y, filter.radius(), height);
+
+                for row in rows.0..rows.1 {
+                    for column in columns.0..columns.1 {
+                        #[allow(clippy::cast_precision_loss)]
+                        let weight =
+                            filter.weight(Point2::new(column as f64, row as f64) - position);
+                        let splat =
+                            &mut film_tile.splats[(row - splat_y) * splat_width + column - splat_x];
+                        splat.weighted_sum += weight * color;
+                        splat.weight += weight;
+                    }
+                }
+            }
+
+            on_tile(&tile);
+        });
+
+        self.passes += 1;
+    }
+
+    pub fn passes(&self) -> usize {
+        self.passes
+    }
+
+    pub fn tile_count(&self) -> usize {
+        self.tiles.len()
+    }
+
+    /// Whether another film has the same dimensions, filter and tiles as this one, so that
+    /// rendering into it would give the same result as rendering into this one.
+    pub fn has_layout_of(&self, other: &Film) -> bool {
+        self.width == other.width
+            && self.height == other.height
+            && self.filter == other.filter
+            && self.tiles.len() == other.tiles.len()
+            && self
+                .tiles
+                .iter()
+                .zip(&other.tiles)
+                .all(|(a, b)| a.tile == b.tile)
+    }
+
+    /// Get a film with only the tiles at the given indices into this one's tiles, to be
+    /// rendered on its own.
+    pub fn select_tiles(&self, indices: &[usize]) -> Film {
+        Film {
+            width: self.width,
+            height: self.height,
+            filter: self.filter,
+            passes: self.passes,
+            tiles: indices.iter().map(|&i| self.tiles[i].clone()).collect(),
+        }
+    }
+
+    /// Replace this film's tiles with the ones rendered in `other`, which has to be made up
+    /// of tiles from this film's layout.
+    pub fn merge(&mut self, other: Film) -> Result<()> {
+        if (self.width, self.height, self.filter) != (other.width, other.height, other.filter) {
+            bail!("can't merge films with different dimensions or filters");
+        }
+
+        for film_tile in other.tiles {
+            let Some(existing) = self.tiles.iter_mut().find(|t| t.tile == film_tile.tile) else {
+                bail!("can't merge a film with a tile that isn't in this one");
+            };
+            *existing = film_tile;
+        }
+
+        Ok(())
+    }
+
+    /// Write the film to a checkpoint file, along with the settings its samples were taken
+    /// with, which [`Film::load_checkpoint`] can read back.
+    ///
+    /// The checkpoint is written next to `path` and then moved into place, so an existing
+    /// checkpoint is never left half overwritten.
+    pub fn save_checkpoint(&self, path: &Path, sampling: &SamplingSettings) -> Result<()> {
+        let write = || -> Result<()> {
+            let temporary_path = path.with_extension("checkpoint.partial");
+            let mut writer = BufWriter::new(File::create(&temporary_path)?);
+
+            writer.write_all(CHECKPOINT_MAGIC)?;
+            bincode::serialize_into(&mut writer, sampling)?;
+            bincode::serialize_into(&mut writer, self)?;
+            writer.into_inner()?.sync_all()?;
+
+            fs::rename(&temporary_path, path)?;
+            Ok(())
+        };
+
+        write().with_context(|| format!("couldn't write checkpoint {}", path.display()))
+    }
+
+    /// Read a film and the settings its samples were taken with back from a checkpoint file
+    /// written by [`Film::save_checkpoint`].
+    pub fn load_checkpoint(path: &Path) -> Result<(Self, SamplingSettings)> {
+        let read = || -> Result<(Self, SamplingSettings)> {
+            let mut reader = BufReader::new(File::open(path)?);
+
+            let mut magic = [0; CHECKPOINT_MAGIC.len()];
+            reader.read_exact(&mut magic)?;
+            if magic != *CHECKPOINT_MAGIC {
+                bail!("not a checkpoint file from this version of rayromano");
+            }
+
+            let sampling = bincode::deserialize_from(&mut reader)?;
+            Ok((bincode::deserialize_from(reader)?, sampling))
+        };
+
+        read().with_context(|| format!("couldn't read checkpoint {}", path.display()))
+    }
+
+    /// The largest relative error of any pixel, as estimated by
+    /// [`FilmPixel::relative_error`].
+    pub fn max_relative_error(&self) -> f64 {
+        self.tiles
+            .par_iter()
+            .flat_map_iter(|tile| tile.pixels.iter().map(FilmPixel::relative_error))
+            .reduce(|| 0., f64::max)
+    }
+
+    /// Resolve the film into an image, with each pixel the filtered average of the samples
+    /// around it.
+    ///
+    /// The tiles' splats are added up in order of their position, rather than the order
+    /// they were rendered in, so that the tile order doesn't change the result.
+    pub fn image(&self) -> Rgb32FImage {
+        let width = self.width as usize;
+        let mut splats = vec![Splat::default(); width * self.height as usize];
+
+        let mut tiles: Vec<&FilmTile> = self.tiles.iter().collect();
+        tiles.sort_unstable_by_key(|film_tile| (film_tile.tile.y, film_tile.tile.x));
+        for film_tile in tiles {
+            let rows = film_tile.splats.chunks(film_tile.splat_width as usize);
+
+            for (row, tile_row) in (film_tile.splat_y as usize..).zip(rows) {
+                let start = row * width + film_tile.splat_x as usize;
+
+                for (splat, tile_splat) in splats[start..].iter_mut().zip(tile_row) {
+                    splat.weighted_sum += tile_splat.weighted_sum;
+                    splat.weight += tile_splat.weight;
+                }
+            }
+        }
+
+        let mut image = Rgb32FImage::new(self.width, self.height);
+        for (pixel, splat) in image.pixels_mut().zip(&splats) {
+            // Filters with negative lobes can leave a pixel with no meaningful weight.
+            let color = if splat.weight > 0. {
+                splat.weighted_sum / splat.weight
+            } else {
+                Vector3::zeros()
+            };
+
+            #[allow(clippy::cast_possible_truncation)]
+            {
+                *pixel = Rgb([color.x as f32, color.y as f32, color.z as f32]);
+            }
+        }
+
+        image
+    }
+}
+
+/// The bytes every checkpoint file starts with, which change whenever the format does.
+const CHECKPOINT_MAGIC: &[u8] = b"rayromano checkpoint 3\n";
+
+/// The range of pixel indices within `radius` of `position` along one axis, clipped to the
+/// image, as a start and an exclusive end.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn pixel_range(position: f64, radius: f64, len: usize) -> (usize, usize) {
+    let start = (position - radius).ceil().max(0.) as usize;
+    let end = ((position + radius).floor() + 1.).max(0.) as usize;
+
+    (start.min(len), end.min(len))
+}
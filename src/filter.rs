@@ -0,0 +1,114 @@
+use std::f64::consts::TAU;
+
+use clap::ValueEnum;
+use na::Vector2;
+use serde::{Deserialize, Serialize};
+
+/// The shapes of filter that samples can be reconstructed into pixels with.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FilterKind {
+    /// Every sample within the radius counts equally.
+    #[default]
+    Box,
+    /// A weight falling off linearly to zero at the radius.
+    Tent,
+    /// A Gaussian with a standard deviation of a third of the radius, shifted down to reach
+    /// zero at the radius.
+    Gaussian,
+    /// The Mitchell–Netravali cubic with B = C = 1/3, which sharpens slightly with its
+    /// negative lobes.
+    Mitchell,
+    /// The four-term Blackman–Harris window.
+    BlackmanHarris,
+}
+
+impl FilterKind {
+    /// The radius the filter is usually used with, in pixels.
+    pub fn default_radius(self) -> f64 {
+        match self {
+            FilterKind::Box => 0.5,
+            FilterKind::Tent => 1.,
+            FilterKind::Gaussian => 1.5,
+            FilterKind::Mitchell | FilterKind::BlackmanHarris => 2.,
+        }
+    }
+}
+
+/// A pixel reconstruction filter, deciding how much a sample counts towards each of the
+/// pixels around it.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Filter {
+    kind: FilterKind,
+    radius: f64,
+}
+
+impl Default for Filter {
+    fn default() -> Self {
+        Self::new(FilterKind::default(), None)
+    }
+}
+
+impl Filter {
+    /// Create a filter reaching `radius` pixels out from each sample, or the kind's usual
+    /// radius if there isn't one.
+    pub fn new(kind: FilterKind, radius: Option<f64>) -> Self {
+        Self {
+            kind,
+            radius: radius.unwrap_or_else(|| kind.default_radius()),
+        }
+    }
+
+    /// How far the filter reaches, in pixels.
+    pub fn radius(&self) -> f64 {
+        self.radius
+    }
+
+    /// The weight of a sample at `offset` pixels away from a pixel's center.
+    ///
+    /// The filters are all separable, so this is the product of the weight along each axis.
+    pub fn weight(&self, offset: Vector2<f64>) -> f64 {
+        self.weight_1d(offset.x) * self.weight_1d(offset.y)
+    }
+
+    fn weight_1d(&self, x: f64) -> f64 {
+        let radius = self.radius;
+        let x = x.abs();
+        if x > radius {
+            return 0.;
+        }
+
+        match self.kind {
+            FilterKind::Box => 1.,
+            FilterKind::Tent => radius - x,
+            FilterKind::Gaussian => {
+                let sigma = radius / 3.;
+                let gaussian = |x: f64| (-x * x / (2. * sigma * sigma)).exp();
+                gaussian(x) - gaussian(radius)
+            }
+            FilterKind::Mitchell => mitchell(2. * x / radius),
+            FilterKind::BlackmanHarris => {
+                let t = 0.5 + x / (2. * radius);
+                0.358_75 - 0.488_29 * (TAU * t).cos() + 0.141_28 * (2. * TAU * t).cos()
+                    - 0.011_68 * (3. * TAU * t).cos()
+            }
+        }
+    }
+}
+
+/// The Mitchell–Netravali cubic with B = C = 1/3, over [0, 2].
+fn mitchell(x: f64) -> f64 {
+    const B: f64 = 1. / 3.;
+    const C: f64 = 1. / 3.;
+
+    let value = if x < 1. {
+        (12. - 9. * B - 6. * C) * x * x * x + (-18. + 12. * B + 6. * C) * x * x + (6. - 2. * B)
+    } else {
+        (-B - 6. * C) * x * x * x
+            + (6. * B + 30. * C) * x * x
+            + (-12. * B - 48. * C) * x
+            + (8. * B + 24. * C)
+    };
+
+    value / 6.
+}
@@ -1,3 +1,21 @@
+pub mod aabb;
+pub mod bvh;
+pub mod clipped_sphere;
+pub mod cone;
+pub mod csg;
+pub mod cuboid;
+pub mod cylinder;
+pub mod disk;
 pub mod interval;
+pub mod mesh;
+pub mod moving;
+pub mod obj;
+pub mod plane;
+pub mod polynomial;
+pub mod quad;
 pub mod ray;
+pub mod sdf;
 pub mod sphere;
+pub mod torus;
+pub mod transformed;
+pub mod triangle;
@@ -0,0 +1,172 @@
+use crate::geometry::interval::Interval;
+use crate::geometry::ray::Ray;
+use na::{Point3, Vector3};
+
+/// An axis-aligned bounding box, stored as one [`Interval`] per axis.
+#[derive(Copy, Clone, Debug)]
+pub struct Aabb {
+    pub x: Interval,
+    pub y: Interval,
+    pub z: Interval,
+}
+
+impl Aabb {
+    /// The box containing nothing; enclosing it with any other box yields that box.
+    pub const EMPTY: Aabb = Aabb {
+        x: Interval::EMPTY,
+        y: Interval::EMPTY,
+        z: Interval::EMPTY,
+    };
+
+    /// The box containing everything, for objects that go on forever.
+    pub const UNIVERSE: Aabb = Aabb {
+        x: Interval::UNIVERSE,
+        y: Interval::UNIVERSE,
+        z: Interval::UNIVERSE,
+    };
+
+    /// The smallest width any axis is allowed to have, so that flat primitives still get
+    /// hit by the slab test.
+    const MIN_WIDTH: f64 = 1e-4;
+
+    /// Create a new bounding box from its extent along each axis.
+    pub fn new(x: Interval, y: Interval, z: Interval) -> Self {
+        Self { x, y, z }.pad_to_minimums()
+    }
+
+    /// Create the bounding box with `a` and `b` as opposite corners.
+    pub fn from_points(a: &Point3<f64>, b: &Point3<f64>) -> Self {
+        Self::new(
+            Interval::new(a.x.min(b.x), a.x.max(b.x)),
+            Interval::new(a.y.min(b.y), a.y.max(b.y)),
+            Interval::new(a.z.min(b.z), a.z.max(b.z)),
+        )
+    }
+
+    /// The smallest bounding box containing both `a` and `b`.
+    pub fn enclosing(a: &Aabb, b: &Aabb) -> Self {
+        Self {
+            x: Interval::enclosing(&a.x, &b.x),
+            y: Interval::enclosing(&a.y, &b.y),
+            z: Interval::enclosing(&a.z, &b.z),
+        }
+    }
+
+    /// The same box moved by `offset`.
+    pub fn offset(&self, offset: &Vector3<f64>) -> Self {
+        Self {
+            x: self.x + offset.x,
+            y: self.y + offset.y,
+            z: self.z + offset.z,
+        }
+    }
+
+    /// Get the extent of the box along axis `n`, where 0, 1 and 2 are x, y and z.
+    pub fn axis(&self, n: usize) -> &Interval {
+        match n {
+            1 => &self.y,
+            2 => &self.z,
+            _ => &self.x,
+        }
+    }
+
+    /// The index of the axis along which the box is largest.
+    pub fn longest_axis(&self) -> usize {
+        if self.x.size() > self.y.size() {
+            if self.x.size() > self.z.size() {
+                0
+            } else {
+                2
+            }
+        } else if self.y.size() > self.z.size() {
+            1
+        } else {
+            2
+        }
+    }
+
+    /// Whether the box has finite extents, so that it can be put in a BVH.
+    pub fn is_bounded(&self) -> bool {
+        [self.x, self.y, self.z]
+            .iter()
+            .all(|axis| axis.min.is_finite() && axis.max.is_finite())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.x.is_empty() || self.y.is_empty() || self.z.is_empty()
+    }
+
+    pub fn centroid(&self) -> Point3<f64> {
+        Point3::new(self.x.midpoint(), self.y.midpoint(), self.z.midpoint())
+    }
+
+    /// The surface area of the box, used as the cost heuristic when building a BVH.
+    pub fn surface_area(&self) -> f64 {
+        if self.is_empty() {
+            return 0.;
+        }
+
+        let (dx, dy, dz) = (self.x.size(), self.y.size(), self.z.size());
+        2. * (dx * dy + dy * dz + dz * dx)
+    }
+
+    /// Check whether `ray` passes through the box within `t_interval`.
+    ///
+    /// `inverse_direction` is the component-wise reciprocal of the ray's direction, which
+    /// callers testing many boxes against the same ray should compute only once.
+    pub fn hits(&self, ray: &Ray, inverse_direction: &Vector3<f64>, t_interval: Interval) -> bool {
+        self.slab_interval(ray, inverse_direction, t_interval)
+            .is_some()
+    }
+
+    /// Get the part of `t_interval` over which `ray` is inside the box, if there is any.
+    pub fn clip(&self, ray: &Ray, t_interval: Interval) -> Option<Interval> {
+        let direction = ray.direction();
+        let inverse_direction = Vector3::new(1. / direction.x, 1. / direction.y, 1. / direction.z);
+
+        self.slab_interval(ray, &inverse_direction, t_interval)
+    }
+
+    fn slab_interval(
+        &self,
+        ray: &Ray,
+        inverse_direction: &Vector3<f64>,
+        t_interval: Interval,
+    ) -> Option<Interval> {
+        let origin = ray.origin();
+        let mut t_min = t_interval.min;
+        let mut t_max = t_interval.max;
+
+        for axis in 0..3 {
+            let extent = self.axis(axis);
+            let t0 = (extent.min - origin[axis]) * inverse_direction[axis];
+            let t1 = (extent.max - origin[axis]) * inverse_direction[axis];
+
+            let (t0, t1) = if t0 < t1 { (t0, t1) } else { (t1, t0) };
+            t_min = t_min.max(t0);
+            t_max = t_max.min(t1);
+
+            if t_max <= t_min {
+                return None;
+            }
+        }
+
+        Some(Interval::new(t_min, t_max))
+    }
+
+    fn pad_to_minimums(self) -> Self {
+        let pad = |interval: Interval| {
+            if interval.size() < Self::MIN_WIDTH {
+                interval.expand(Self::MIN_WIDTH)
+            } else {
+                interval
+            }
+        };
+
+        Self {
+            x: pad(self.x),
+            y: pad(self.y),
+            z: pad(self.z),
+        }
+    }
+}
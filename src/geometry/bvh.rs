@@ -0,0 +1,284 @@
+use crate::geometry::aabb::Aabb;
+use crate::geometry::interval::Interval;
+use crate::geometry::ray::{Hit, Hittable, Ray};
+use na::{Point3, Vector3};
+
+/// The number of buckets centroids are binned into when evaluating split candidates.
+const SAH_BUCKETS: usize = 12;
+/// Nodes with at most this many objects are never split.
+const MIN_LEAF_SIZE: usize = 2;
+/// Nodes with more than this many objects are always split, even if the SAH disagrees.
+const MAX_LEAF_SIZE: usize = 8;
+/// The cost of traversing an interior node, relative to intersecting one object.
+const TRAVERSAL_COST: f64 = 0.5;
+
+/// A bounding volume hierarchy over a list of objects.
+///
+/// The tree is built once, using the surface area heuristic to choose splits, and stored
+/// flattened in depth-first order so that traversal doesn't need to chase pointers.
+///
+/// Objects without finite bounds, like infinite planes, can't be sorted into the tree, so
+/// they're kept to the side and tested against every ray.
+pub struct Bvh {
+    objects: Vec<Box<dyn Hittable>>,
+    nodes: Vec<BvhNode>,
+    unbounded: Vec<Box<dyn Hittable>>,
+}
+
+#[derive(Copy, Clone, Debug)]
+struct BvhNode {
+    bbox: Aabb,
+    kind: BvhNodeKind,
+}
+
+#[derive(Copy, Clone, Debug)]
+enum BvhNodeKind {
+    /// An interior node. Its left child immediately follows it in the node list.
+    Interior { right_child: usize, axis: usize },
+    /// A leaf node, referring to a contiguous range of objects.
+    Leaf { first: usize, count: usize },
+}
+
+/// The information about each object needed while building the tree.
+struct BuildObject {
+    index: usize,
+    bbox: Aabb,
+    centroid: Point3<f64>,
+}
+
+impl Bvh {
+    pub fn new(objects: Vec<Box<dyn Hittable>>) -> Self {
+        let (objects, unbounded): (Vec<_>, Vec<_>) = objects.into_iter().partition(|object| {
+            let bbox = object.bounding_box();
+            bbox.is_empty() || bbox.is_bounded()
+        });
+
+        let mut build_objects: Vec<BuildObject> = objects
+            .iter()
+            .enumerate()
+            .map(|(index, object)| {
+                let bbox = object.bounding_box();
+                BuildObject {
+                    index,
+                    bbox,
+                    centroid: bbox.centroid(),
+                }
+            })
+            .collect();
+
+        let mut nodes = Vec::with_capacity(2 * objects.len());
+        if !build_objects.is_empty() {
+            Self::build(&mut nodes, &mut build_objects, 0);
+        }
+
+        // Put the objects in the order the leaves refer to them in.
+        let mut slots: Vec<Option<Box<dyn Hittable>>> = objects.into_iter().map(Some).collect();
+        let objects = build_objects
+            .iter()
+            .map(|build_object| slots[build_object.index].take().unwrap())
+            .collect();
+
+        Self {
+            objects,
+            nodes,
+            unbounded,
+        }
+    }
+
+    /// Recursively build the subtree over `build_objects`, which start at `offset` in the
+    /// final object list, returning the index of its root node.
+    fn build(nodes: &mut Vec<BvhNode>, build_objects: &mut [BuildObject], offset: usize) -> usize {
+        let bbox = build_objects.iter().fold(Aabb::EMPTY, |acc, object| {
+            Aabb::enclosing(&acc, &object.bbox)
+        });
+        let node_index = nodes.len();
+        let leaf = BvhNode {
+            bbox,
+            kind: BvhNodeKind::Leaf {
+                first: offset,
+                count: build_objects.len(),
+            },
+        };
+        nodes.push(leaf);
+
+        if build_objects.len() <= MIN_LEAF_SIZE {
+            return node_index;
+        }
+
+        let centroid_bounds = build_objects.iter().fold(Aabb::EMPTY, |acc, object| {
+            Aabb::enclosing(&acc, &Aabb::from_points(&object.centroid, &object.centroid))
+        });
+        let axis = centroid_bounds.longest_axis();
+        let axis_extent = *centroid_bounds.axis(axis);
+
+        let Some(split) = Self::choose_split(build_objects, &bbox, axis, &axis_extent) else {
+            return node_index;
+        };
+
+        let (left, right) = build_objects.split_at_mut(split);
+        Self::build(nodes, left, offset);
+        let right_child = Self::build(nodes, right, offset + split);
+
+        nodes[node_index].kind = BvhNodeKind::Interior { right_child, axis };
+        node_index
+    }
+
+    /// Partition `build_objects` along `axis` at the cheapest bucket boundary according to
+    /// the surface area heuristic, returning the number of objects on the left side, or
+    /// `None` if the objects should stay in a single leaf.
+    fn choose_split(
+        build_objects: &mut [BuildObject],
+        bbox: &Aabb,
+        axis: usize,
+        axis_extent: &Interval,
+    ) -> Option<usize> {
+        let count = build_objects.len();
+
+        if axis_extent.size() <= 0. {
+            // Every centroid is in the same place, so no bucketing can separate them.
+            return if count > MAX_LEAF_SIZE {
+                Some(count / 2)
+            } else {
+                None
+            };
+        }
+
+        #[allow(
+            clippy::cast_precision_loss,
+            clippy::cast_possible_truncation,
+            clippy::cast_sign_loss
+        )]
+        let bucket_of = |centroid: &Point3<f64>| {
+            let relative = (centroid[axis] - axis_extent.min) / axis_extent.size();
+            ((relative * SAH_BUCKETS as f64) as usize).min(SAH_BUCKETS - 1)
+        };
+
+        let mut bucket_counts = [0usize; SAH_BUCKETS];
+        let mut bucket_bounds = [Aabb::EMPTY; SAH_BUCKETS];
+        for object in build_objects.iter() {
+            let bucket = bucket_of(&object.centroid);
+            bucket_counts[bucket] += 1;
+            bucket_bounds[bucket] = Aabb::enclosing(&bucket_bounds[bucket], &object.bbox);
+        }
+
+        // Sweep from both ends to get the cost of splitting after each bucket.
+        let mut costs = [0f64; SAH_BUCKETS - 1];
+        let mut left_bounds = Aabb::EMPTY;
+        let mut left_count = 0;
+        for split in 0..SAH_BUCKETS - 1 {
+            left_bounds = Aabb::enclosing(&left_bounds, &bucket_bounds[split]);
+            left_count += bucket_counts[split];
+            #[allow(clippy::cast_precision_loss)]
+            {
+                costs[split] = left_count as f64 * left_bounds.surface_area();
+            }
+        }
+        let mut right_bounds = Aabb::EMPTY;
+        let mut right_count = 0;
+        for split in (0..SAH_BUCKETS - 1).rev() {
+            right_bounds = Aabb::enclosing(&right_bounds, &bucket_bounds[split + 1]);
+            right_count += bucket_counts[split + 1];
+            #[allow(clippy::cast_precision_loss)]
+            {
+                costs[split] += right_count as f64 * right_bounds.surface_area();
+            }
+        }
+
+        let (best_split, best_cost) = costs
+            .iter()
+            .enumerate()
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(split, cost)| (split, TRAVERSAL_COST + cost / bbox.surface_area()))?;
+
+        #[allow(clippy::cast_precision_loss)]
+        let leaf_cost = count as f64;
+        if count <= MAX_LEAF_SIZE && leaf_cost <= best_cost {
+            return None;
+        }
+
+        let mut left = 0;
+        for i in 0..count {
+            if bucket_of(&build_objects[i].centroid) <= best_split {
+                build_objects.swap(i, left);
+                left += 1;
+            }
+        }
+
+        if left == 0 || left == count {
+            // The heuristic couldn't separate anything, so fall back to a median split.
+            build_objects.sort_unstable_by(|a, b| a.centroid[axis].total_cmp(&b.centroid[axis]));
+            Some(count / 2)
+        } else {
+            Some(left)
+        }
+    }
+}
+
+impl Hittable for Bvh {
+    fn hits(&self, ray: &Ray, t_interval: Interval) -> Option<Hit> {
+        let mut best_hit = None;
+        let mut closest_so_far = t_interval.max;
+
+        for object in &self.unbounded {
+            if let Some(hit) = object.hits(ray, Interval::new(t_interval.min, closest_so_far)) {
+                closest_so_far = hit.t;
+                best_hit = Some(hit);
+            }
+        }
+
+        if self.nodes.is_empty() {
+            return best_hit;
+        }
+
+        let direction = ray.direction();
+        let inverse_direction = Vector3::new(1. / direction.x, 1. / direction.y, 1. / direction.z);
+
+        let mut stack = Vec::with_capacity(64);
+        stack.push(0);
+
+        while let Some(node_index) = stack.pop() {
+            let node = &self.nodes[node_index];
+            let current_interval = Interval::new(t_interval.min, closest_so_far);
+
+            if !node.bbox.hits(ray, &inverse_direction, current_interval) {
+                continue;
+            }
+
+            match node.kind {
+                BvhNodeKind::Leaf { first, count } => {
+                    for object in &self.objects[first..first + count] {
+                        let possible_hit =
+                            object.hits(ray, Interval::new(t_interval.min, closest_so_far));
+
+                        if let Some(Hit { t, .. }) = possible_hit {
+                            best_hit = possible_hit;
+                            closest_so_far = t;
+                        }
+                    }
+                }
+                BvhNodeKind::Interior { right_child, axis } => {
+                    // Visit the child nearer to the ray's origin first, so that the
+                    // farther one can often be skipped.
+                    let left_child = node_index + 1;
+                    if direction[axis] < 0. {
+                        stack.push(left_child);
+                        stack.push(right_child);
+                    } else {
+                        stack.push(right_child);
+                        stack.push(left_child);
+                    }
+                }
+            }
+        }
+
+        best_hit
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let tree_box = self.nodes.first().map_or(Aabb::EMPTY, |root| root.bbox);
+
+        self.unbounded.iter().fold(tree_box, |acc, object| {
+            Aabb::enclosing(&acc, &object.bounding_box())
+        })
+    }
+}
@@ -0,0 +1,99 @@
+use crate::geometry::aabb::Aabb;
+use crate::geometry::interval::Interval;
+use crate::geometry::polynomial::quadratic_roots;
+use crate::geometry::ray::{Hit, Hittable, Ray};
+use crate::materials::Material;
+use crate::util::azimuth;
+use na::{Point2, Point3, Unit, Vector3};
+
+/// A sphere with its top and bottom cut off, and optionally a wedge cut out of it, leaving
+/// an open shell.
+///
+/// The texture coordinates stretch across what's left, going around the y axis in `u` and
+/// from the bottom cut to the top one in `v`.
+#[derive(Clone)]
+pub struct ClippedSphere {
+    pub center: Point3<f64>,
+    pub radius: f64,
+    /// The heights relative to the center below and above which the sphere is cut off.
+    pub y_min: f64,
+    pub y_max: f64,
+    /// The angle around the y axis that the sphere sweeps through, in radians, starting
+    /// from -x and turning towards +z.
+    pub phi_max: f64,
+    pub material: Box<dyn Material>,
+}
+
+impl ClippedSphere {
+    #[allow(unused)]
+    pub fn new(
+        center: Point3<f64>,
+        radius: f64,
+        y_min: f64,
+        y_max: f64,
+        phi_max: f64,
+        material: impl Material + 'static,
+    ) -> Self {
+        Self {
+            center,
+            radius,
+            y_min,
+            y_max,
+            phi_max,
+            material: Box::new(material),
+        }
+    }
+
+    /// Get the polar angle, up from the bottom pole, at height `y` relative to the center.
+    fn theta_at(&self, y: f64) -> f64 {
+        (-y / self.radius).clamp(-1., 1.).acos()
+    }
+}
+
+impl Hittable for ClippedSphere {
+    fn hits(&self, ray: &Ray, t_interval: Interval) -> Option<Hit> {
+        let origin = ray.origin() - self.center;
+        let direction = ray.direction();
+
+        let (t0, t1) = quadratic_roots(
+            direction.magnitude_squared(),
+            origin.dot(direction),
+            origin.magnitude_squared() - self.radius * self.radius,
+        )?;
+
+        // If the nearer hit is cut away, the ray can still hit the inside of the shell.
+        let (t, local, phi) = [t0, t1].into_iter().find_map(|t| {
+            let local = origin + t * direction;
+            let phi = azimuth(&local);
+
+            (t_interval.surrounds(t)
+                && (self.y_min..=self.y_max).contains(&local.y)
+                && phi <= self.phi_max)
+                .then_some((t, local, phi))
+        })?;
+
+        let theta_min = self.theta_at(self.y_min);
+        let theta_max = self.theta_at(self.y_max);
+        let uv = Point2::new(
+            phi / self.phi_max,
+            (self.theta_at(local.y) - theta_min) / (theta_max - theta_min),
+        );
+
+        Some(Hit::new(
+            direction,
+            ray.at(t),
+            t,
+            Unit::new_normalize(local),
+            uv,
+            self.material.clone(),
+        ))
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        // Only the cuts are worth tightening the box around; a wedge rarely shrinks it much.
+        Aabb::from_points(
+            &(self.center + Vector3::new(-self.radius, self.y_min, -self.radius)),
+            &(self.center + Vector3::new(self.radius, self.y_max, self.radius)),
+        )
+    }
+}
@@ -0,0 +1,102 @@
+use std::f64::consts::TAU;
+
+use crate::geometry::aabb::Aabb;
+use crate::geometry::interval::Interval;
+use crate::geometry::polynomial::quadratic_roots;
+use crate::geometry::ray::{Hit, Hittable, Ray};
+use crate::materials::Material;
+use crate::util::azimuth;
+use na::{Point2, Point3, Unit, Vector3};
+
+/// A cone standing upright on `base`, the center of its bottom, narrowing to a point
+/// `height` above it.
+///
+/// Cones in any other orientation can be made with a transform. Texture coordinates work
+/// like a [`Cylinder`](crate::geometry::cylinder::Cylinder)'s.
+#[derive(Clone)]
+pub struct Cone {
+    pub base: Point3<f64>,
+    pub radius: f64,
+    pub height: f64,
+    /// Whether the bottom is closed, making the cone a solid.
+    pub capped: bool,
+    pub material: Box<dyn Material>,
+}
+
+impl Cone {
+    #[allow(unused)]
+    pub fn new(
+        base: Point3<f64>,
+        radius: f64,
+        height: f64,
+        capped: bool,
+        material: impl Material + 'static,
+    ) -> Self {
+        Self {
+            base,
+            radius,
+            height,
+            capped,
+            material: Box::new(material),
+        }
+    }
+}
+
+impl Hittable for Cone {
+    fn hits(&self, ray: &Ray, t_interval: Interval) -> Option<Hit> {
+        let origin = ray.origin() - self.base;
+        let direction = ray.direction();
+        let mut closest: Option<(f64, Vector3<f64>, Point2<f64>)> = None;
+
+        // The side is where x² + z² = k (height - y)², which is a double cone meeting at the
+        // apex, so hits on the upper half have to be thrown out.
+        let k = (self.radius / self.height).powi(2);
+        let below_apex = self.height - origin.y;
+        let a =
+            direction.x * direction.x + direction.z * direction.z - k * direction.y * direction.y;
+        let half_b = origin.x * direction.x + origin.z * direction.z + k * below_apex * direction.y;
+        let c = origin.x * origin.x + origin.z * origin.z - k * below_apex * below_apex;
+        if let Some((t0, t1)) = quadratic_roots(a, half_b, c) {
+            for t in [t0, t1] {
+                let point = origin + t * direction;
+                if t_interval.surrounds(t) && (0. ..=self.height).contains(&point.y) {
+                    let normal = Vector3::new(point.x, k * (self.height - point.y), point.z);
+                    let uv = Point2::new(azimuth(&point) / TAU, point.y / self.height);
+                    closest = Some((t, normal, uv));
+                    break;
+                }
+            }
+        }
+
+        if self.capped && direction.y != 0. {
+            let t = -origin.y / direction.y;
+            let point = origin + t * direction;
+            let distance_squared = point.x * point.x + point.z * point.z;
+
+            if t_interval.surrounds(t)
+                && closest.is_none_or(|(closest_t, ..)| t < closest_t)
+                && distance_squared <= self.radius * self.radius
+            {
+                let uv = Point2::new(azimuth(&point) / TAU, distance_squared.sqrt() / self.radius);
+                closest = Some((t, -Vector3::y(), uv));
+            }
+        }
+
+        let (t, normal, uv) = closest?;
+        Some(Hit::new(
+            direction,
+            ray.at(t),
+            t,
+            Unit::new_normalize(normal),
+            uv,
+            self.material.clone(),
+        ))
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        Aabb::from_points(
+            &(self.base - Vector3::new(self.radius, 0., self.radius)),
+            &(self.base + Vector3::new(self.radius, self.height, self.radius)),
+        )
+    }
+}
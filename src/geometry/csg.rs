@@ -0,0 +1,124 @@
+use crate::geometry::aabb::Aabb;
+use crate::geometry::interval::Interval;
+use crate::geometry::ray::{Hit, Hittable, Ray, Span};
+
+/// The ways constructive solid geometry can combine two solids.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Operation {
+    /// Everything inside either solid.
+    Union,
+    /// Everything inside both solids.
+    Intersection,
+    /// Everything inside the left solid, but not the right one.
+    Difference,
+}
+
+impl Operation {
+    /// Whether a point is inside the combination, given whether it's inside each solid.
+    fn contains(self, in_left: bool, in_right: bool) -> bool {
+        match self {
+            Operation::Union => in_left || in_right,
+            Operation::Intersection => in_left && in_right,
+            Operation::Difference => in_left && !in_right,
+        }
+    }
+}
+
+/// Two solids combined into one by constructive solid geometry.
+///
+/// Both solids have to be closed, so that their [`spans`](Hittable::spans) make sense. Each
+/// part of the combination's surface keeps the material of the solid it came from.
+pub struct Csg<A: Hittable, B: Hittable> {
+    operation: Operation,
+    left: A,
+    right: B,
+    bounding_box: Aabb,
+}
+
+impl<A: Hittable, B: Hittable> Csg<A, B> {
+    pub fn new(operation: Operation, left: A, right: B) -> Self {
+        // Nothing outside the left solid is part of an intersection or a difference.
+        let bounding_box = match operation {
+            Operation::Union => Aabb::enclosing(&left.bounding_box(), &right.bounding_box()),
+            Operation::Intersection | Operation::Difference => left.bounding_box(),
+        };
+
+        Self {
+            operation,
+            left,
+            right,
+            bounding_box,
+        }
+    }
+
+    /// Get the combination's spans along the whole of `ray`.
+    ///
+    /// The solids' spans are needed from wherever they start, even before the part of the ray
+    /// being looked at, to know whether the ray starts out inside each of them.
+    fn combined_spans(&self, ray: &Ray) -> Vec<Span> {
+        let left = self.left.spans(ray, Interval::UNIVERSE);
+        if left.is_empty() && self.operation != Operation::Union {
+            return Vec::new();
+        }
+        let right = self.right.spans(ray, Interval::UNIVERSE);
+
+        // Every point where the ray goes in or out of either solid, as the hit, whether it's
+        // on the left solid, and whether the ray goes in there.
+        let mut crossings: Vec<(Hit, bool, bool)> = left
+            .into_iter()
+            .map(|span| (span, true))
+            .chain(right.into_iter().map(|span| (span, false)))
+            .flat_map(|(span, is_left)| [(span.entry, is_left, true), (span.exit, is_left, false)])
+            .collect();
+        crossings.sort_by(|(a, ..), (b, ..)| a.t.total_cmp(&b.t));
+
+        let mut spans = Vec::new();
+        let mut entry = None;
+        let (mut in_left, mut in_right) = (false, false);
+
+        for (mut hit, is_left, entering) in crossings {
+            let was_inside = self.operation.contains(in_left, in_right);
+            if is_left {
+                in_left = entering;
+            } else {
+                in_right = entering;
+            }
+            let inside = self.operation.contains(in_left, in_right);
+
+            if inside == was_inside {
+                continue;
+            }
+
+            // Where the right solid cuts into a difference, its surface faces the other way to
+            // the combination's, so which side the ray is on has to come from the combination.
+            hit.front_face = inside;
+            if inside {
+                entry = Some(hit);
+            } else if let Some(entry) = entry.take() {
+                spans.push(Span { entry, exit: hit });
+            }
+        }
+
+        spans
+    }
+}
+
+impl<A: Hittable, B: Hittable> Hittable for Csg<A, B> {
+    fn hits(&self, ray: &Ray, t_interval: Interval) -> Option<Hit> {
+        self.combined_spans(ray)
+            .into_iter()
+            .flat_map(|span| [span.entry, span.exit])
+            .find(|hit| t_interval.surrounds(hit.t))
+    }
+
+    fn spans(&self, ray: &Ray, t_interval: Interval) -> Vec<Span> {
+        self.combined_spans(ray)
+            .into_iter()
+            .filter(|span| t_interval.surrounds(span.entry.t) && t_interval.surrounds(span.exit.t))
+            .collect()
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bounding_box
+    }
+}
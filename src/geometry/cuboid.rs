@@ -0,0 +1,68 @@
+use crate::geometry::aabb::Aabb;
+use crate::geometry::interval::Interval;
+use crate::geometry::quad::Quad;
+use crate::geometry::ray::{Hit, Hittable, Ray};
+use crate::materials::Material;
+use na::{Point3, Vector3};
+
+/// An axis-aligned box, made of the six quads around its faces.
+///
+/// Each face has its own texture coordinates, running across it from corner to corner.
+#[derive(Clone)]
+pub struct Cuboid {
+    faces: [Quad; 6],
+    bounding_box: Aabb,
+}
+
+impl Cuboid {
+    /// Create the box with `a` and `b` as opposite corners.
+    pub fn new(a: &Point3<f64>, b: &Point3<f64>, material: Box<dyn Material>) -> Self {
+        let min = a.inf(b);
+        let max = a.sup(b);
+        let size = max - min;
+        let dx = Vector3::new(size.x, 0., 0.);
+        let dy = Vector3::new(0., size.y, 0.);
+        let dz = Vector3::new(0., 0., size.z);
+
+        // Each face's sides are ordered so that its normal points out of the box.
+        let face = |corner: Point3<f64>, u: Vector3<f64>, v: Vector3<f64>| Quad {
+            corner,
+            u,
+            v,
+            material: material.clone(),
+        };
+        let faces = [
+            face(Point3::new(min.x, min.y, max.z), dx, dy),
+            face(Point3::new(max.x, min.y, max.z), -dz, dy),
+            face(Point3::new(max.x, min.y, min.z), -dx, dy),
+            face(min, dz, dy),
+            face(Point3::new(min.x, max.y, max.z), dx, -dz),
+            face(min, dx, dz),
+        ];
+
+        Self {
+            faces,
+            bounding_box: Aabb::from_points(&min, &max),
+        }
+    }
+}
+
+impl Hittable for Cuboid {
+    fn hits(&self, ray: &Ray, t_interval: Interval) -> Option<Hit> {
+        let mut best_hit = None;
+        let mut closest_so_far = t_interval.max;
+
+        for face in &self.faces {
+            if let Some(hit) = face.hits(ray, Interval::new(t_interval.min, closest_so_far)) {
+                closest_so_far = hit.t;
+                best_hit = Some(hit);
+            }
+        }
+
+        best_hit
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bounding_box
+    }
+}
@@ -0,0 +1,101 @@
+use std::f64::consts::TAU;
+
+use crate::geometry::aabb::Aabb;
+use crate::geometry::interval::Interval;
+use crate::geometry::polynomial::quadratic_roots;
+use crate::geometry::ray::{Hit, Hittable, Ray};
+use crate::materials::Material;
+use crate::util::azimuth;
+use na::{Point2, Point3, Unit, Vector3};
+
+/// A cylinder standing upright on `base`, the center of its bottom.
+///
+/// Cylinders in any other orientation can be made with a transform. The side's texture
+/// coordinates go around it in `u` and up it in `v`, and the caps' go around them in `u` and
+/// out from their centers in `v`.
+#[derive(Clone)]
+pub struct Cylinder {
+    pub base: Point3<f64>,
+    pub radius: f64,
+    pub height: f64,
+    /// Whether the ends are closed, making the cylinder a solid rather than a tube.
+    pub capped: bool,
+    pub material: Box<dyn Material>,
+}
+
+impl Cylinder {
+    #[allow(unused)]
+    pub fn new(
+        base: Point3<f64>,
+        radius: f64,
+        height: f64,
+        capped: bool,
+        material: impl Material + 'static,
+    ) -> Self {
+        Self {
+            base,
+            radius,
+            height,
+            capped,
+            material: Box::new(material),
+        }
+    }
+}
+
+impl Hittable for Cylinder {
+    fn hits(&self, ray: &Ray, t_interval: Interval) -> Option<Hit> {
+        let origin = ray.origin() - self.base;
+        let direction = ray.direction();
+        let mut closest: Option<(f64, Vector3<f64>, Point2<f64>)> = None;
+
+        let a = direction.x * direction.x + direction.z * direction.z;
+        let half_b = origin.x * direction.x + origin.z * direction.z;
+        let c = origin.x * origin.x + origin.z * origin.z - self.radius * self.radius;
+        if let Some((t0, t1)) = quadratic_roots(a, half_b, c) {
+            // The nearer root can be off either end, and then the farther one is on the inside.
+            for t in [t0, t1] {
+                let point = origin + t * direction;
+                if t_interval.surrounds(t) && (0. ..=self.height).contains(&point.y) {
+                    let normal = Vector3::new(point.x, 0., point.z);
+                    let uv = Point2::new(azimuth(&point) / TAU, point.y / self.height);
+                    closest = Some((t, normal, uv));
+                    break;
+                }
+            }
+        }
+
+        if self.capped && direction.y != 0. {
+            for (height, normal) in [(0., -Vector3::y()), (self.height, Vector3::y())] {
+                let t = (height - origin.y) / direction.y;
+                let point = origin + t * direction;
+                let distance_squared = point.x * point.x + point.z * point.z;
+
+                if t_interval.surrounds(t)
+                    && closest.is_none_or(|(closest_t, ..)| t < closest_t)
+                    && distance_squared <= self.radius * self.radius
+                {
+                    let uv =
+                        Point2::new(azimuth(&point) / TAU, distance_squared.sqrt() / self.radius);
+                    closest = Some((t, normal, uv));
+                }
+            }
+        }
+
+        let (t, normal, uv) = closest?;
+        Some(Hit::new(
+            direction,
+            ray.at(t),
+            t,
+            Unit::new_normalize(normal),
+            uv,
+            self.material.clone(),
+        ))
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        Aabb::from_points(
+            &(self.base - Vector3::new(self.radius, 0., self.radius)),
+            &(self.base + Vector3::new(self.radius, self.height, self.radius)),
+        )
+    }
+}
@@ -0,0 +1,78 @@
+use std::f64::consts::PI;
+
+use crate::geometry::aabb::Aabb;
+use crate::geometry::interval::Interval;
+use crate::geometry::ray::{Hit, Hittable, Ray};
+use crate::materials::Material;
+use crate::util::orthonormal_basis;
+use na::{Point2, Point3, Unit, Vector3};
+
+/// A flat, round disk facing along `normal`.
+#[derive(Clone)]
+pub struct Disk {
+    pub center: Point3<f64>,
+    pub normal: Unit<Vector3<f64>>,
+    pub radius: f64,
+    pub material: Box<dyn Material>,
+}
+
+impl Disk {
+    #[allow(unused)]
+    pub fn new(
+        center: Point3<f64>,
+        normal: Vector3<f64>,
+        radius: f64,
+        material: impl Material + 'static,
+    ) -> Self {
+        Self {
+            center,
+            normal: Unit::new_normalize(normal),
+            radius,
+            material: Box::new(material),
+        }
+    }
+}
+
+impl Hittable for Disk {
+    fn hits(&self, ray: &Ray, t_interval: Interval) -> Option<Hit> {
+        let denominator = self.normal.dot(ray.direction());
+        if denominator.abs() < 1e-12 {
+            return None;
+        }
+
+        let t = self.normal.dot(&(self.center - ray.origin())) / denominator;
+        if !t_interval.surrounds(t) {
+            return None;
+        }
+
+        let point = ray.at(t);
+        let offset = point - self.center;
+        let distance_squared = offset.magnitude_squared();
+        if distance_squared > self.radius * self.radius {
+            return None;
+        }
+
+        // The texture coordinates go around the disk in `u`, and out from its center in `v`.
+        let (tangent, bitangent) = orthonormal_basis(&self.normal);
+        let angle = f64::atan2(offset.dot(&bitangent), offset.dot(&tangent)) + PI;
+        let uv = Point2::new(angle / (2. * PI), distance_squared.sqrt() / self.radius);
+
+        Some(Hit::new(
+            ray.direction(),
+            point,
+            t,
+            self.normal,
+            uv,
+            self.material.clone(),
+        ))
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        // How far the rim reaches along each axis, which is less the more the disk faces it.
+        let extent = self
+            .normal
+            .map(|n| self.radius * (1. - n * n).max(0.).sqrt());
+
+        Aabb::from_points(&(self.center - extent), &(self.center + extent))
+    }
+}
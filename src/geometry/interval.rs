@@ -1,5 +1,7 @@
 #![allow(dead_code)]
 
+use std::ops::Add;
+
 #[derive(Copy, Clone, Debug)]
 pub struct Interval {
     pub min: f64,
@@ -7,14 +9,39 @@ pub struct Interval {
 }
 
 impl Interval {
+    /// The interval containing no values at all.
+    pub const EMPTY: Interval = Interval {
+        min: f64::INFINITY,
+        max: f64::NEG_INFINITY,
+    };
+
+    /// The interval containing every value.
+    pub const UNIVERSE: Interval = Interval {
+        min: f64::NEG_INFINITY,
+        max: f64::INFINITY,
+    };
+
     pub fn new(min: f64, max: f64) -> Self {
         Interval { min, max }
     }
 
+    /// The smallest interval containing both `a` and `b`.
+    pub fn enclosing(a: &Interval, b: &Interval) -> Self {
+        Interval::new(a.min.min(b.min), a.max.max(b.max))
+    }
+
     pub fn size(&self) -> f64 {
         self.max - self.min
     }
 
+    pub fn midpoint(&self) -> f64 {
+        0.5 * (self.min + self.max)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.min > self.max
+    }
+
     pub fn contains(&self, x: f64) -> bool {
         x >= self.min && x <= self.max
     }
@@ -26,6 +53,21 @@ impl Interval {
     pub fn clamp(&self, x: f64) -> f64 {
         x.clamp(self.min, self.max)
     }
+
+    /// Grow the interval by `delta` in total, split evenly between both ends.
+    pub fn expand(&self, delta: f64) -> Self {
+        let padding = delta / 2.;
+        Interval::new(self.min - padding, self.max + padding)
+    }
+}
+
+impl Add<f64> for Interval {
+    type Output = Interval;
+
+    /// Shift the interval by `offset`.
+    fn add(self, offset: f64) -> Self::Output {
+        Interval::new(self.min + offset, self.max + offset)
+    }
 }
 
 impl Default for Interval {
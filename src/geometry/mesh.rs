@@ -0,0 +1,115 @@
+use std::sync::Arc;
+
+use crate::geometry::aabb::Aabb;
+use crate::geometry::bvh::Bvh;
+use crate::geometry::interval::Interval;
+use crate::geometry::ray::{Hit, Hittable, Ray};
+use crate::geometry::triangle;
+use crate::materials::Material;
+use na::{Point2, Point3, Unit, Vector3};
+
+/// The vertex buffers and faces making up a triangle mesh.
+pub struct MeshData {
+    pub positions: Vec<Point3<f64>>,
+    /// Per-vertex normals, either empty or one for each position.
+    pub normals: Vec<Unit<Vector3<f64>>>,
+    /// Per-vertex texture coordinates, either empty or one for each position.
+    pub uvs: Vec<Point2<f64>>,
+    pub faces: Vec<Face>,
+    pub materials: Vec<Box<dyn Material>>,
+}
+
+/// A triangle in a mesh, given as indices into its vertex buffers and material list.
+#[derive(Copy, Clone, Debug)]
+pub struct Face {
+    pub indices: [u32; 3],
+    pub material: u32,
+}
+
+/// A triangle mesh, where every triangle shares the same vertex buffers.
+pub struct Mesh {
+    triangles: Bvh,
+}
+
+impl Mesh {
+    pub fn new(data: MeshData) -> Self {
+        let data = Arc::new(data);
+        let triangles = (0..data.faces.len())
+            .map(|face| {
+                Box::new(MeshTriangle {
+                    data: Arc::clone(&data),
+                    face,
+                }) as Box<dyn Hittable>
+            })
+            .collect();
+
+        Self {
+            triangles: Bvh::new(triangles),
+        }
+    }
+}
+
+impl Hittable for Mesh {
+    fn hits(&self, ray: &Ray, t_interval: Interval) -> Option<Hit> {
+        self.triangles.hits(ray, t_interval)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.triangles.bounding_box()
+    }
+}
+
+/// One face of a mesh, as seen by the mesh's BVH.
+struct MeshTriangle {
+    data: Arc<MeshData>,
+    face: usize,
+}
+
+impl MeshTriangle {
+    fn face(&self) -> &Face {
+        &self.data.faces[self.face]
+    }
+
+    fn vertices(&self) -> [Point3<f64>; 3] {
+        self.face()
+            .indices
+            .map(|index| self.data.positions[index as usize])
+    }
+
+    fn normals(&self) -> Option<[Unit<Vector3<f64>>; 3]> {
+        if self.data.normals.is_empty() {
+            None
+        } else {
+            Some(
+                self.face()
+                    .indices
+                    .map(|index| self.data.normals[index as usize]),
+            )
+        }
+    }
+
+    fn uvs(&self) -> Option<[Point2<f64>; 3]> {
+        if self.data.uvs.is_empty() {
+            None
+        } else {
+            Some(
+                self.face()
+                    .indices
+                    .map(|index| self.data.uvs[index as usize]),
+            )
+        }
+    }
+}
+
+impl Hittable for MeshTriangle {
+    fn hits(&self, ray: &Ray, t_interval: Interval) -> Option<Hit> {
+        let intersection = triangle::intersect(ray, &self.vertices(), t_interval)?;
+        let material = self.data.materials[self.face().material as usize].clone();
+
+        Some(intersection.into_hit(ray, self.normals().as_ref(), self.uvs().as_ref(), material))
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        triangle::bounding_box(&self.vertices())
+    }
+}
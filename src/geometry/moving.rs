@@ -0,0 +1,107 @@
+use crate::geometry::aabb::Aabb;
+use crate::geometry::interval::Interval;
+use crate::geometry::ray::{Hit, Hittable, Ray, Span};
+use na::Vector3;
+
+/// A path through space, given as the offsets reached at a series of times and followed
+/// in straight lines between them.
+///
+/// Before the first keyframe and after the last one, the offset stays where they leave it.
+#[derive(Clone, Debug)]
+pub struct Motion {
+    /// The keyframes as `(time, offset)` pairs, sorted by time.
+    keyframes: Vec<(f64, Vector3<f64>)>,
+}
+
+impl Motion {
+    /// Create a motion through the given keyframes, in any order.
+    pub fn new(mut keyframes: Vec<(f64, Vector3<f64>)>) -> Self {
+        keyframes.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+        Self { keyframes }
+    }
+
+    /// Create a motion at constant speed, from no offset at time 0 to `offset` at time 1.
+    pub fn linear(offset: Vector3<f64>) -> Self {
+        Self::new(vec![(0., Vector3::zeros()), (1., offset)])
+    }
+
+    /// Get the offset at `time`.
+    pub fn at(&self, time: f64) -> Vector3<f64> {
+        let next = self.keyframes.partition_point(|&(t, _)| t <= time);
+
+        let previous = next.checked_sub(1).map(|i| self.keyframes[i]);
+
+        match (previous, self.keyframes.get(next)) {
+            (Some((t0, offset0)), Some(&(t1, offset1))) => {
+                offset0.lerp(&offset1, (time - t0) / (t1 - t0))
+            }
+            (Some((_, offset)), None) | (None, Some(&(_, offset))) => offset,
+            (None, None) => Vector3::zeros(),
+        }
+    }
+
+    /// The offsets the path turns at, which between them enclose all of it.
+    fn corners(&self) -> impl Iterator<Item = &Vector3<f64>> {
+        self.keyframes.iter().map(|(_, offset)| offset)
+    }
+}
+
+/// An object moving along a [`Motion`], which rays hit wherever it is at their time.
+pub struct Moving<H: Hittable> {
+    object: H,
+    motion: Motion,
+    bounding_box: Aabb,
+}
+
+impl<H: Hittable> Moving<H> {
+    pub fn new(object: H, motion: Motion) -> Self {
+        let object_box = object.bounding_box();
+        let bounding_box = motion.corners().fold(Aabb::EMPTY, |acc, offset| {
+            Aabb::enclosing(&acc, &object_box.offset(offset))
+        });
+
+        Self {
+            object,
+            motion,
+            bounding_box,
+        }
+    }
+}
+
+impl<H: Hittable> Moving<H> {
+    /// Move `ray` the opposite way to the object at its time, rather than moving the object,
+    /// returning it with the offset to move hits on the object back by.
+    fn moved_ray(&self, ray: &Ray) -> (Ray, Vector3<f64>) {
+        let offset = self.motion.at(ray.time());
+
+        (
+            Ray::new(ray.origin() - offset, *ray.direction(), ray.time()),
+            offset,
+        )
+    }
+}
+
+impl<H: Hittable> Hittable for Moving<H> {
+    fn hits(&self, ray: &Ray, t_interval: Interval) -> Option<Hit> {
+        let (moved_ray, offset) = self.moved_ray(ray);
+
+        let mut hit = self.object.hits(&moved_ray, t_interval)?;
+        hit.point += offset;
+        Some(hit)
+    }
+
+    fn spans(&self, ray: &Ray, t_interval: Interval) -> Vec<Span> {
+        let (moved_ray, offset) = self.moved_ray(ray);
+
+        let mut spans = self.object.spans(&moved_ray, t_interval);
+        for span in &mut spans {
+            span.entry.point += offset;
+            span.exit.point += offset;
+        }
+        spans
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bounding_box
+    }
+}
@@ -0,0 +1,188 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use na::{Point2, Point3, Unit, Vector3};
+
+use crate::geometry::mesh::{Face, Mesh, MeshData};
+use crate::materials::{Dielectric, DiffuseLight, Lambertian, Material, Metal};
+use crate::textures::ImageTexture;
+
+/// Load a Wavefront OBJ file as a single [`Mesh`].
+///
+/// Each `usemtl` group gets the material that `overrides` maps its name to if there is one,
+/// and otherwise an approximation of its definition in the accompanying MTL file. Faces
+/// without any material use `default_material`.
+#[allow(unused)]
+pub fn load_obj(
+    path: &Path,
+    overrides: &HashMap<String, Box<dyn Material>>,
+    default_material: Box<dyn Material>,
+) -> Result<Mesh> {
+    ObjFile::read(path)?.mesh(overrides, default_material, |path| {
+        Ok(Arc::new(ImageTexture::load(path)?))
+    })
+}
+
+/// A Wavefront OBJ file that's been read, but not made into a [`Mesh`] yet, so that it can
+/// be made into any number of them with different materials.
+pub struct ObjFile {
+    /// The directory the file is in, which its MTL file's textures are relative to.
+    dir: PathBuf,
+    models: Vec<tobj::Model>,
+    materials: Vec<tobj::Material>,
+    /// Why the MTL file couldn't be loaded, if it couldn't be, in which case every face gets
+    /// the default material.
+    pub mtl_error: Option<tobj::LoadError>,
+}
+
+impl ObjFile {
+    /// Read an OBJ file, along with the MTL file it refers to if there is one.
+    ///
+    /// The mesh is still usable without its MTL file, so failing to load that is left for
+    /// the caller to report from [`ObjFile::mtl_error`].
+    pub fn read(path: &Path) -> Result<Self> {
+        let (models, materials) = tobj::load_obj(path, &tobj::GPU_LOAD_OPTIONS)
+            .with_context(|| format!("couldn't load OBJ file {}", path.display()))?;
+        let (materials, mtl_error) = match materials {
+            Ok(materials) => (materials, None),
+            Err(error) => (Vec::new(), Some(error)),
+        };
+
+        Ok(Self {
+            dir: path.parent().unwrap_or(Path::new("")).to_owned(),
+            models,
+            materials,
+            mtl_error,
+        })
+    }
+
+    /// Make the file into a single [`Mesh`], with its materials picked like [`load_obj`]'s.
+    ///
+    /// The images for any of the MTL file's materials with a diffuse texture map come from
+    /// `load_texture`.
+    pub fn mesh(
+        &self,
+        overrides: &HashMap<String, Box<dyn Material>>,
+        default_material: Box<dyn Material>,
+        mut load_texture: impl FnMut(&Path) -> Result<Arc<ImageTexture>>,
+    ) -> Result<Mesh> {
+        // The default material goes last, after one for each material in the MTL file.
+        let mut materials: Vec<Box<dyn Material>> = self
+            .materials
+            .iter()
+            .map(|material| match overrides.get(&material.name) {
+                Some(material) => Ok(material.clone()),
+                None => convert_material(material, &self.dir, &mut load_texture),
+            })
+            .collect::<Result<_>>()?;
+        materials.push(default_material);
+        #[allow(clippy::cast_possible_truncation)]
+        let default_material_index = (materials.len() - 1) as u32;
+
+        let mut data = MeshData {
+            positions: Vec::new(),
+            normals: Vec::new(),
+            uvs: Vec::new(),
+            faces: Vec::new(),
+            materials,
+        };
+
+        let has_normals = self
+            .models
+            .iter()
+            .all(|model| !model.mesh.normals.is_empty());
+        let has_uvs = self
+            .models
+            .iter()
+            .all(|model| !model.mesh.texcoords.is_empty());
+
+        for tobj::Model { mesh, .. } in &self.models {
+            #[allow(clippy::cast_possible_truncation)]
+            let index_offset = data.positions.len() as u32;
+            #[allow(clippy::cast_possible_truncation)]
+            let material = mesh
+                .material_id
+                .map_or(default_material_index, |material| material as u32);
+
+            data.positions.extend(
+                mesh.positions
+                    .chunks_exact(3)
+                    .map(|p| Point3::new(f64::from(p[0]), f64::from(p[1]), f64::from(p[2]))),
+            );
+            if has_normals {
+                data.normals.extend(mesh.normals.chunks_exact(3).map(|n| {
+                    Unit::new_normalize(Vector3::new(
+                        f64::from(n[0]),
+                        f64::from(n[1]),
+                        f64::from(n[2]),
+                    ))
+                }));
+            }
+            if has_uvs {
+                data.uvs.extend(
+                    mesh.texcoords
+                        .chunks_exact(2)
+                        .map(|uv| Point2::new(f64::from(uv[0]), f64::from(uv[1]))),
+                );
+            }
+
+            data.faces
+                .extend(mesh.indices.chunks_exact(3).map(|indices| Face {
+                    indices: [
+                        indices[0] + index_offset,
+                        indices[1] + index_offset,
+                        indices[2] + index_offset,
+                    ],
+                    material,
+                }));
+        }
+
+        Ok(Mesh::new(data))
+    }
+}
+
+/// Pick whichever of our materials best matches an MTL material definition, loading any
+/// diffuse texture map relative to `obj_dir` with `load_texture`.
+fn convert_material(
+    material: &tobj::Material,
+    obj_dir: &Path,
+    load_texture: impl FnOnce(&Path) -> Result<Arc<ImageTexture>>,
+) -> Result<Box<dyn Material>> {
+    let to_color = |c: [f32; 3]| Vector3::new(f64::from(c[0]), f64::from(c[1]), f64::from(c[2]));
+    let diffuse = material
+        .diffuse
+        .map_or(Vector3::new(0.8, 0.8, 0.8), to_color);
+
+    if let Some(emissive) = material.emissive.map(to_color) {
+        if emissive.max() > 0. {
+            return Ok(Box::new(DiffuseLight::new(emissive, 1.)));
+        }
+    }
+
+    let transparent = material.dissolve.is_some_and(|dissolve| dissolve < 1.)
+        || matches!(material.illumination_model, Some(4 | 6 | 7 | 9));
+    if transparent {
+        let refractive_index = material.optical_density.map_or(1.5, f64::from);
+        return Ok(Box::new(Dielectric::new(refractive_index)));
+    }
+
+    // Illumination model 3 is "reflection on", which is as close as MTL gets to "metal".
+    if material.illumination_model == Some(3) {
+        let albedo = material.specular.map_or(diffuse, to_color);
+        // Convert the Phong exponent into a roughness, as it's the closest thing we have
+        // to a fuzz factor.
+        let fuzz = material
+            .shininess
+            .map_or(0., |shininess| (2. / (f64::from(shininess) + 2.)).sqrt());
+        return Ok(Box::new(Metal::new(albedo, fuzz)));
+    }
+
+    if let Some(texture) = &material.diffuse_texture {
+        let texture = load_texture(&obj_dir.join(texture))?;
+        return Ok(Box::new(Lambertian::with_texture(texture)));
+    }
+
+    Ok(Box::new(Lambertian::new(diffuse)))
+}
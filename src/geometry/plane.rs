@@ -0,0 +1,65 @@
+use crate::geometry::aabb::Aabb;
+use crate::geometry::interval::Interval;
+use crate::geometry::ray::{Hit, Hittable, Ray};
+use crate::materials::Material;
+use crate::util::orthonormal_basis;
+use na::{Point2, Point3, Unit, Vector3};
+
+/// An infinite plane through `point`, facing along `normal`.
+///
+/// The plane's texture coordinates repeat every unit along it.
+#[derive(Clone)]
+pub struct Plane {
+    pub point: Point3<f64>,
+    pub normal: Unit<Vector3<f64>>,
+    pub material: Box<dyn Material>,
+}
+
+impl Plane {
+    pub fn new(
+        point: Point3<f64>,
+        normal: Vector3<f64>,
+        material: impl Material + 'static,
+    ) -> Self {
+        Self {
+            point,
+            normal: Unit::new_normalize(normal),
+            material: Box::new(material),
+        }
+    }
+}
+
+impl Hittable for Plane {
+    fn hits(&self, ray: &Ray, t_interval: Interval) -> Option<Hit> {
+        let denominator = self.normal.dot(ray.direction());
+        if denominator.abs() < 1e-12 {
+            return None;
+        }
+
+        let t = self.normal.dot(&(self.point - ray.origin())) / denominator;
+        if !t_interval.surrounds(t) {
+            return None;
+        }
+
+        let point = ray.at(t);
+        let offset = point - self.point;
+        let (tangent, bitangent) = orthonormal_basis(&self.normal);
+        let uv = Point2::new(
+            offset.dot(&tangent).rem_euclid(1.),
+            offset.dot(&bitangent).rem_euclid(1.),
+        );
+
+        Some(Hit::new(
+            ray.direction(),
+            point,
+            t,
+            self.normal,
+            uv,
+            self.material.clone(),
+        ))
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        Aabb::UNIVERSE
+    }
+}
@@ -0,0 +1,117 @@
+//! Finding the real roots of the polynomials that ray-surface intersections reduce to.
+
+/// Solve `a t² + 2 half_b t + c = 0`, returning its real roots in increasing order.
+///
+/// A double root comes back twice, so that the ray grazing a surface still counts as
+/// entering and leaving it.
+pub fn quadratic_roots(a: f64, half_b: f64, c: f64) -> Option<(f64, f64)> {
+    if a == 0. {
+        if half_b == 0. {
+            return None;
+        }
+
+        let t = -c / (2. * half_b);
+        return Some((t, t));
+    }
+
+    let discriminant = half_b * half_b - a * c;
+    if discriminant < 0. {
+        return None;
+    }
+
+    // Avoid subtracting nearly equal numbers, which loses the precision of the smaller root.
+    let q = -(half_b + discriminant.sqrt().copysign(half_b));
+    if q == 0. {
+        return Some((0., 0.));
+    }
+
+    let (t0, t1) = (q / a, c / q);
+    Some(if t0 < t1 { (t0, t1) } else { (t1, t0) })
+}
+
+/// Find the real roots of `coefficients[0] t⁴ + coefficients[1] t³ + ... + coefficients[4]`
+/// in increasing order, using Ferrari's method.
+pub fn quartic_roots(coefficients: [f64; 5]) -> Vec<f64> {
+    let [a, b, c, d, e] = coefficients;
+    if a == 0. {
+        return Vec::new();
+    }
+    let (b, c, d, e) = (b / a, c / a, d / a, e / a);
+
+    // Substituting t = y - b / 4 removes the cubic term.
+    let shift = -b / 4.;
+    let b2 = b * b;
+    let p = c - 3. * b2 / 8.;
+    let q = d - b * c / 2. + b2 * b / 8.;
+    let r = e - b * d / 4. + b2 * c / 16. - 3. * b2 * b2 / 256.;
+
+    let mut roots = Vec::with_capacity(4);
+    if q.abs() < 1e-12 {
+        // The quartic is a quadratic in y².
+        if let Some((z0, z1)) = quadratic_roots(1., p / 2., r) {
+            for z in [z0, z1] {
+                if z >= 0. {
+                    roots.extend([-z.sqrt(), z.sqrt()]);
+                }
+            }
+        }
+    } else {
+        // Any positive root m of the resolvent cubic splits the quartic into two
+        // quadratics, (y² + p / 2 + m)² = (s y - q / 2s)² with s = √(2m).
+        let m = largest_cubic_root(p, p * p / 4. - r, -q * q / 8.);
+        let s = (2. * m).sqrt();
+        for sign in [-1., 1.] {
+            if let Some((y0, y1)) =
+                quadratic_roots(1., sign * s / 2., p / 2. + m - sign * q / (2. * s))
+            {
+                roots.extend([y0, y1]);
+            }
+        }
+    }
+
+    for root in &mut roots {
+        *root = polish([1., b, c, d, e], *root + shift);
+    }
+    roots.sort_unstable_by(f64::total_cmp);
+    roots
+}
+
+/// Get the largest real root of `x³ + a x² + b x + c`.
+fn largest_cubic_root(a: f64, b: f64, c: f64) -> f64 {
+    // Substituting x = y - a / 3 gives y³ + p y + q.
+    let shift = -a / 3.;
+    let p = b - a * a / 3.;
+    let q = 2. * a * a * a / 27. - a * b / 3. + c;
+
+    let discriminant = q * q / 4. + p * p * p / 27.;
+    let y = if discriminant >= 0. {
+        // There's only one real root.
+        let sqrt = discriminant.sqrt();
+        (-q / 2. + sqrt).cbrt() + (-q / 2. - sqrt).cbrt()
+    } else {
+        // There are three, the largest of which is at the first angle.
+        let radius = (-p / 3.).sqrt();
+        let angle = (3. * q / (2. * p * radius)).clamp(-1., 1.).acos() / 3.;
+        2. * radius * angle.cos()
+    };
+
+    y + shift
+}
+
+/// Refine an approximate root of the quartic with a few steps of Newton's method.
+fn polish(coefficients: [f64; 5], mut t: f64) -> f64 {
+    for _ in 0..2 {
+        let (value, derivative) = coefficients
+            .iter()
+            .fold((0., 0.), |(value, derivative), coefficient| {
+                (value * t + coefficient, derivative * t + value)
+            });
+
+        if derivative == 0. {
+            break;
+        }
+        t -= value / derivative;
+    }
+
+    t
+}
@@ -0,0 +1,76 @@
+use crate::geometry::aabb::Aabb;
+use crate::geometry::interval::Interval;
+use crate::geometry::ray::{Hit, Hittable, Ray};
+use crate::materials::Material;
+use na::{Point2, Point3, Unit, Vector3};
+
+/// A parallelogram, with a corner at `corner` and sides along `u` and `v`.
+#[derive(Clone)]
+pub struct Quad {
+    pub corner: Point3<f64>,
+    pub u: Vector3<f64>,
+    pub v: Vector3<f64>,
+    pub material: Box<dyn Material>,
+}
+
+impl Quad {
+    #[allow(unused)]
+    pub fn new(
+        corner: Point3<f64>,
+        u: Vector3<f64>,
+        v: Vector3<f64>,
+        material: impl Material + 'static,
+    ) -> Self {
+        Self {
+            corner,
+            u,
+            v,
+            material: Box::new(material),
+        }
+    }
+}
+
+impl Hittable for Quad {
+    fn hits(&self, ray: &Ray, t_interval: Interval) -> Option<Hit> {
+        let n = self.u.cross(&self.v);
+        let denominator = n.dot(ray.direction());
+
+        // The ray runs parallel to the quad's plane.
+        if denominator.abs() < 1e-12 * n.magnitude() {
+            return None;
+        }
+
+        let t = n.dot(&(self.corner - ray.origin())) / denominator;
+        if !t_interval.surrounds(t) {
+            return None;
+        }
+
+        // Find the hit's coordinates along the sides, which are both in [0, 1] inside it.
+        let point = ray.at(t);
+        let planar = point - self.corner;
+        let w = n / n.magnitude_squared();
+        let alpha = w.dot(&planar.cross(&self.v));
+        let beta = w.dot(&self.u.cross(&planar));
+
+        let unit = Interval::new(0., 1.);
+        if !unit.contains(alpha) || !unit.contains(beta) {
+            return None;
+        }
+
+        Some(Hit::new(
+            ray.direction(),
+            point,
+            t,
+            Unit::new_normalize(n),
+            Point2::new(alpha, beta),
+            self.material.clone(),
+        ))
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let diagonal_1 = Aabb::from_points(&self.corner, &(self.corner + self.u + self.v));
+        let diagonal_2 = Aabb::from_points(&(self.corner + self.u), &(self.corner + self.v));
+
+        Aabb::enclosing(&diagonal_1, &diagonal_2)
+    }
+}
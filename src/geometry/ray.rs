@@ -1,18 +1,27 @@
+use std::sync::Arc;
+
+use crate::geometry::aabb::Aabb;
 use crate::geometry::interval::Interval;
 use crate::materials::Material;
-use na::{Point3, Unit, Vector3};
+use na::{Point2, Point3, Unit, Vector3};
 
 #[derive(Copy, Clone, Debug)]
 /// A ray to be traced.
 pub struct Ray {
     origin: Point3<f64>,
     direction: Vector3<f64>,
+    /// The moment the ray was cast at, which moving objects are hit at their positions for.
+    time: f64,
 }
 
 impl Ray {
-    /// Create a new ray, given its origin and direction.
-    pub fn new(origin: Point3<f64>, direction: Vector3<f64>) -> Self {
-        Self { origin, direction }
+    /// Create a new ray, given its origin, direction and the time it was cast at.
+    pub fn new(origin: Point3<f64>, direction: Vector3<f64>, time: f64) -> Self {
+        Self {
+            origin,
+            direction,
+            time,
+        }
     }
 
     /// Get the ray's origin.
@@ -25,6 +34,11 @@ impl Ray {
         &self.direction
     }
 
+    /// Get the time the ray was cast at.
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
     /// Get a point at distance `t` along the ray.
     pub fn at(&self, t: f64) -> Point3<f64> {
         self.origin + self.direction * t
@@ -35,6 +49,8 @@ pub struct Hit {
     pub point: Point3<f64>,
     pub t: f64,
     pub normal: Unit<Vector3<f64>>,
+    /// The surface's texture coordinates at the hit point.
+    pub uv: Point2<f64>,
     pub material: Box<dyn Material>,
     pub front_face: bool,
 }
@@ -45,6 +61,7 @@ impl Hit {
         point: Point3<f64>,
         t: f64,
         normal: Unit<Vector3<f64>>,
+        uv: Point2<f64>,
         material: Box<dyn Material>,
     ) -> Self {
         let front_face = direction.dot(&normal.into_inner()) < 0.;
@@ -54,14 +71,88 @@ impl Hit {
             point,
             t,
             normal,
+            uv,
             material,
             front_face,
         }
     }
 }
 
+/// A stretch of a ray that's inside a solid object, from the hit where it goes in to the
+/// one where it comes out.
+pub struct Span {
+    pub entry: Hit,
+    pub exit: Hit,
+}
+
 pub trait Hittable: Send + Sync {
     fn hits(&self, ray: &Ray, t_interval: Interval) -> Option<Hit>;
+
+    /// Get every stretch of `ray` inside the object that starts and ends within
+    /// `t_interval`, in order along it, so that solids can be combined with constructive
+    /// solid geometry.
+    ///
+    /// By default, this finds the object's surfaces one after another with
+    /// [`hits`](Hittable::hits), and pairs up the ones the ray goes in through with the ones
+    /// it comes out through, which only makes sense for closed objects.
+    fn spans(&self, ray: &Ray, t_interval: Interval) -> Vec<Span> {
+        let mut spans = Vec::new();
+        let mut entry = None;
+        // How many surfaces deep the ray is, for objects made of overlapping pieces.
+        let mut depth = 0usize;
+        let mut t_min = t_interval.min;
+
+        while let Some(hit) = self.hits(ray, Interval::new(t_min, t_interval.max)) {
+            t_min = hit.t;
+
+            if hit.front_face {
+                depth += 1;
+                if depth == 1 {
+                    entry = Some(hit);
+                }
+            } else if depth > 0 {
+                depth -= 1;
+                if depth == 0 {
+                    if let Some(entry) = entry.take() {
+                        spans.push(Span { entry, exit: hit });
+                    }
+                }
+            }
+        }
+
+        spans
+    }
+
+    /// Get a box that contains the whole object, for use in acceleration structures.
+    fn bounding_box(&self) -> Aabb;
+}
+
+impl<H: Hittable + ?Sized> Hittable for Box<H> {
+    fn hits(&self, ray: &Ray, t_interval: Interval) -> Option<Hit> {
+        (**self).hits(ray, t_interval)
+    }
+
+    fn spans(&self, ray: &Ray, t_interval: Interval) -> Vec<Span> {
+        (**self).spans(ray, t_interval)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        (**self).bounding_box()
+    }
+}
+
+impl<H: Hittable + ?Sized> Hittable for Arc<H> {
+    fn hits(&self, ray: &Ray, t_interval: Interval) -> Option<Hit> {
+        (**self).hits(ray, t_interval)
+    }
+
+    fn spans(&self, ray: &Ray, t_interval: Interval) -> Vec<Span> {
+        (**self).spans(ray, t_interval)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        (**self).bounding_box()
+    }
 }
 
 impl Hittable for Vec<Box<dyn Hittable>> {
@@ -80,4 +171,10 @@ impl Hittable for Vec<Box<dyn Hittable>> {
 
         best_hit
     }
+
+    fn bounding_box(&self) -> Aabb {
+        self.iter().fold(Aabb::EMPTY, |acc, hittable| {
+            Aabb::enclosing(&acc, &hittable.bounding_box())
+        })
+    }
 }
@@ -0,0 +1,148 @@
+//! Shapes described by signed distance functions, which are drawn by sphere tracing rather
+//! than by solving for where rays meet them.
+
+pub mod combinators;
+pub mod mandelbulb;
+pub mod primitives;
+
+use crate::geometry::aabb::Aabb;
+use crate::geometry::interval::Interval;
+use crate::geometry::ray::{Hit, Hittable, Ray};
+use crate::materials::Material;
+use na::{Point2, Point3, Unit, Vector3};
+
+/// How close to a surface rays have to get to hit it, unless they're told otherwise.
+pub const DEFAULT_EPSILON: f64 = 1e-4;
+/// The most steps rays take before they're taken to have missed, unless they're told
+/// otherwise.
+pub const DEFAULT_MAX_STEPS: u32 = 256;
+
+/// A shape given by the signed distance from any point to its surface, which is negative
+/// inside it and positive outside.
+///
+/// The distance is allowed to be an underestimate, which only makes tracing it slower, but
+/// never an overestimate, which would make rays step through the surface.
+pub trait Sdf: Send + Sync {
+    fn distance(&self, point: &Point3<f64>) -> f64;
+
+    /// Get a box that contains the whole surface, outside of which rays aren't traced.
+    fn bounding_box(&self) -> Aabb;
+
+    /// Get the surface's outward normal at `point`.
+    ///
+    /// By default, this is the gradient of the distance, estimated from its value at the
+    /// corners of a tiny tetrahedron around the point.
+    fn normal(&self, point: &Point3<f64>) -> Unit<Vector3<f64>> {
+        const H: f64 = 1e-6;
+
+        let gradient = [
+            Vector3::new(1., -1., -1.),
+            Vector3::new(-1., -1., 1.),
+            Vector3::new(-1., 1., -1.),
+            Vector3::new(1., 1., 1.),
+        ]
+        .iter()
+        .map(|corner| corner * self.distance(&(point + corner * H)))
+        .sum();
+
+        Unit::new_normalize(gradient)
+    }
+}
+
+impl<S: Sdf + ?Sized> Sdf for Box<S> {
+    fn distance(&self, point: &Point3<f64>) -> f64 {
+        (**self).distance(point)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        (**self).bounding_box()
+    }
+
+    fn normal(&self, point: &Point3<f64>) -> Unit<Vector3<f64>> {
+        (**self).normal(point)
+    }
+}
+
+/// A signed distance function made into an object, by sphere tracing: stepping along each
+/// ray by the distance to the surface, which can't skip over it, until it's close enough to
+/// count as a hit.
+///
+/// Signed distance functions have no natural texture coordinates, so every hit's are zero.
+pub struct SphereTraced<S: Sdf> {
+    pub sdf: S,
+    pub material: Box<dyn Material>,
+    /// How close to the surface a ray has to get to hit it.
+    pub epsilon: f64,
+    /// The most steps a ray takes before it's taken to have missed.
+    pub max_steps: u32,
+}
+
+impl<S: Sdf> SphereTraced<S> {
+    #[allow(unused)]
+    pub fn new(sdf: S, material: impl Material + 'static) -> Self {
+        Self {
+            sdf,
+            material: Box::new(material),
+            epsilon: DEFAULT_EPSILON,
+            max_steps: DEFAULT_MAX_STEPS,
+        }
+    }
+}
+
+impl<S: Sdf> Hittable for SphereTraced<S> {
+    fn hits(&self, ray: &Ray, t_interval: Interval) -> Option<Hit> {
+        let clipped = self.sdf.bounding_box().clip(ray, t_interval)?;
+        let speed = ray.direction().magnitude();
+        let mut t = clipped.min;
+
+        // A ray starting inside the shape, like one refracted into it, is traced until it
+        // comes back out, by following the distance with its sign flipped. One starting on
+        // the surface, like one that's just bounced off it, is on the side it's heading into,
+        // and can't hit the surface until it's gotten away from it, so that it doesn't hit
+        // the one it's leaving again. Rays coming into the box from outside it are outside
+        // the shape, and can hit it straight away.
+        let (side, mut left_surface) = if clipped.min > t_interval.min {
+            (1., true)
+        } else {
+            let start = ray.at(t);
+            let start_distance = self.sdf.distance(&start);
+
+            if start_distance.abs() < self.epsilon {
+                let heading = self.sdf.normal(&start).dot(ray.direction());
+                (heading.signum(), false)
+            } else {
+                (start_distance.signum(), false)
+            }
+        };
+
+        for _ in 0..self.max_steps {
+            let distance = side * self.sdf.distance(&ray.at(t));
+
+            if distance >= self.epsilon {
+                left_surface = true;
+            } else if left_surface {
+                let point = ray.at(t);
+
+                return Some(Hit::new(
+                    ray.direction(),
+                    point,
+                    t,
+                    self.sdf.normal(&point),
+                    Point2::origin(),
+                    self.material.clone(),
+                ));
+            }
+
+            t += distance.max(self.epsilon) / speed;
+            if t >= clipped.max {
+                return None;
+            }
+        }
+
+        None
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.sdf.bounding_box()
+    }
+}
@@ -0,0 +1,138 @@
+//! Ways of building new signed distance functions out of others.
+
+use crate::geometry::aabb::Aabb;
+use crate::geometry::interval::Interval;
+use crate::geometry::sdf::Sdf;
+use na::{Point3, Vector3};
+
+/// Grow `bbox` by `margin` on every side.
+fn grow(bbox: &Aabb, margin: f64) -> Aabb {
+    Aabb::new(
+        bbox.x.expand(2. * margin),
+        bbox.y.expand(2. * margin),
+        bbox.z.expand(2. * margin),
+    )
+}
+
+/// Two shapes joined together, with the seam between them filled in smoothly.
+pub struct SmoothUnion<A: Sdf, B: Sdf> {
+    pub a: A,
+    pub b: B,
+    /// How far from the seam the shapes blend into each other. At zero, this is an ordinary
+    /// union.
+    pub smoothness: f64,
+}
+
+impl<A: Sdf, B: Sdf> Sdf for SmoothUnion<A, B> {
+    fn distance(&self, point: &Point3<f64>) -> f64 {
+        let (a, b) = (self.a.distance(point), self.b.distance(point));
+        if self.smoothness <= 0. {
+            return a.min(b);
+        }
+
+        // The polynomial smooth minimum, which only differs from the minimum where the
+        // distances are within `smoothness` of each other.
+        let h = (self.smoothness - (a - b).abs()).max(0.) / self.smoothness;
+        a.min(b) - h * h * self.smoothness / 4.
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        // The blend can bulge out past both shapes by at most a quarter of the smoothness.
+        grow(
+            &Aabb::enclosing(&self.a.bounding_box(), &self.b.bounding_box()),
+            self.smoothness.max(0.) / 4.,
+        )
+    }
+}
+
+/// A grid of copies of a shape, `spacing` apart, with `limit` more on each side of the
+/// original along each axis.
+///
+/// The shape has to fit inside one grid cell around the origin, or the copies get cut off.
+pub struct Repeat<S: Sdf> {
+    pub sdf: S,
+    pub spacing: Vector3<f64>,
+    pub limit: Vector3<f64>,
+}
+
+impl<S: Sdf> Sdf for Repeat<S> {
+    fn distance(&self, point: &Point3<f64>) -> f64 {
+        // Find the nearest copy, and move the point into the original's cell from there.
+        let cell = point
+            .coords
+            .component_div(&self.spacing)
+            .map(f64::round)
+            .zip_map(&self.limit, |cell, limit| cell.clamp(-limit, limit));
+
+        self.sdf
+            .distance(&(point - cell.component_mul(&self.spacing)))
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let reach = self.limit.component_mul(&self.spacing);
+        let bbox = self.sdf.bounding_box();
+
+        Aabb::enclosing(&bbox.offset(&-reach), &bbox.offset(&reach))
+    }
+}
+
+/// A shape twisted around the y axis, by `rate` radians for every unit up it.
+pub struct Twist<S: Sdf> {
+    sdf: S,
+    rate: f64,
+    /// The farthest the shape reaches from the y axis.
+    radius: f64,
+}
+
+impl<S: Sdf> Twist<S> {
+    pub fn new(sdf: S, rate: f64) -> Self {
+        let bbox = sdf.bounding_box();
+        let radius = [bbox.x.min, bbox.x.max]
+            .iter()
+            .flat_map(|x| [bbox.z.min, bbox.z.max].map(|z| x.hypot(z)))
+            .fold(0., f64::max);
+
+        Self { sdf, rate, radius }
+    }
+}
+
+impl<S: Sdf> Sdf for Twist<S> {
+    fn distance(&self, point: &Point3<f64>) -> f64 {
+        let (sin, cos) = (self.rate * point.y).sin_cos();
+        let untwisted = Point3::new(
+            cos * point.x + sin * point.z,
+            point.y,
+            cos * point.z - sin * point.x,
+        );
+
+        // Twisting stretches space more the farther it is from the axis, so the distance has
+        // to be scaled down to stay an underestimate.
+        self.sdf.distance(&untwisted) / (1. + self.rate.abs() * self.radius)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let bbox = self.sdf.bounding_box();
+
+        Aabb::new(
+            Interval::new(-self.radius, self.radius),
+            bbox.y,
+            Interval::new(-self.radius, self.radius),
+        )
+    }
+}
+
+/// A shape with its edges rounded off, by growing it by `radius` in every direction.
+pub struct Round<S: Sdf> {
+    pub sdf: S,
+    pub radius: f64,
+}
+
+impl<S: Sdf> Sdf for Round<S> {
+    fn distance(&self, point: &Point3<f64>) -> f64 {
+        self.sdf.distance(point) - self.radius
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        grow(&self.sdf.bounding_box(), self.radius)
+    }
+}
@@ -0,0 +1,60 @@
+//! The Mandelbulb, a three-dimensional relative of the Mandelbrot set.
+
+use crate::geometry::aabb::Aabb;
+use crate::geometry::sdf::Sdf;
+use na::{Point3, Vector3};
+
+/// The distance from the origin past which points are known to escape.
+const BAILOUT: f64 = 2.;
+
+/// The Mandelbulb fractal, centered on the origin with its axis of symmetry along y.
+///
+/// Points are iterated under z ↦ zⁿ + c, where taking a power of a point raises its distance
+/// from the origin to it and multiplies its spherical angles by it. Its distance can only be
+/// estimated, from how fast points escape.
+pub struct Mandelbulb {
+    /// The power the points are raised to, which is 8 in the classic Mandelbulb.
+    pub power: f64,
+    /// How many times points are iterated, which is how fine the detail gets.
+    pub iterations: u32,
+}
+
+impl Sdf for Mandelbulb {
+    fn distance(&self, point: &Point3<f64>) -> f64 {
+        let c = point.coords;
+        let mut z = c;
+        // The derivative of the distance from the origin, with respect to c.
+        let mut derivative = 1.;
+        let mut radius = z.magnitude();
+
+        for _ in 0..self.iterations {
+            if radius > BAILOUT {
+                break;
+            }
+            if radius == 0. {
+                // The point has landed on the origin, where its angles are undefined. From
+                // there it goes back to where it started, so it never escapes and is inside.
+                return 0.;
+            }
+
+            let theta = (z.y / radius).clamp(-1., 1.).acos() * self.power;
+            let phi = f64::atan2(z.z, z.x) * self.power;
+            derivative = self.power * radius.powf(self.power - 1.) * derivative + 1.;
+
+            let (sin_theta, cos_theta) = theta.sin_cos();
+            let (sin_phi, cos_phi) = phi.sin_cos();
+            z = radius.powf(self.power)
+                * Vector3::new(sin_theta * cos_phi, cos_theta, sin_theta * sin_phi)
+                + c;
+            radius = z.magnitude();
+        }
+
+        0.5 * radius.ln() * radius / derivative
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let extent = Vector3::repeat(BAILOUT);
+
+        Aabb::from_points(&Point3::from(-extent), &Point3::from(extent))
+    }
+}
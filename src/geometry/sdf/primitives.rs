@@ -0,0 +1,67 @@
+//! Simple shapes with exact signed distance functions, to build more interesting ones from.
+
+use crate::geometry::aabb::Aabb;
+use crate::geometry::sdf::Sdf;
+use na::{Point3, Vector2, Vector3};
+
+pub struct SdfSphere {
+    pub center: Point3<f64>,
+    pub radius: f64,
+}
+
+impl Sdf for SdfSphere {
+    fn distance(&self, point: &Point3<f64>) -> f64 {
+        (point - self.center).magnitude() - self.radius
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let extent = Vector3::repeat(self.radius);
+
+        Aabb::from_points(&(self.center - extent), &(self.center + extent))
+    }
+}
+
+/// An axis-aligned box, reaching `half_size` from its center along each axis.
+pub struct SdfBox {
+    pub center: Point3<f64>,
+    pub half_size: Vector3<f64>,
+}
+
+impl Sdf for SdfBox {
+    fn distance(&self, point: &Point3<f64>) -> f64 {
+        // How far outside each pair of faces the point is, which is negative between them.
+        let q = (point - self.center).abs() - self.half_size;
+
+        q.sup(&Vector3::zeros()).magnitude() + q.max().min(0.)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        Aabb::from_points(
+            &(self.center - self.half_size),
+            &(self.center + self.half_size),
+        )
+    }
+}
+
+/// A torus lying flat around `center`, like a [`Torus`](crate::geometry::torus::Torus).
+pub struct SdfTorus {
+    pub center: Point3<f64>,
+    pub major_radius: f64,
+    pub minor_radius: f64,
+}
+
+impl Sdf for SdfTorus {
+    fn distance(&self, point: &Point3<f64>) -> f64 {
+        let p = point - self.center;
+        let ring = Vector2::new(p.xz().magnitude() - self.major_radius, p.y);
+
+        ring.magnitude() - self.minor_radius
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let outer = self.major_radius + self.minor_radius;
+        let extent = Vector3::new(outer, self.minor_radius, outer);
+
+        Aabb::from_points(&(self.center - extent), &(self.center + extent))
+    }
+}
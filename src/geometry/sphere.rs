@@ -1,7 +1,10 @@
+use crate::geometry::aabb::Aabb;
 use crate::geometry::interval::Interval;
 use crate::geometry::ray::{Hit, Hittable, Ray};
 use crate::materials::Material;
-use na::{Point3, Unit};
+use crate::util::azimuth;
+use na::{Point2, Point3, Unit, Vector3};
+use std::f64::consts::PI;
 
 #[derive(Clone)]
 pub struct Sphere {
@@ -18,6 +21,15 @@ impl Sphere {
             material: Box::new(material),
         }
     }
+
+    /// Get the texture coordinates of a point on the unit sphere, with `u` going around the
+    /// y axis starting from -x, and `v` going from the bottom pole to the top.
+    fn uv_at(point: &Vector3<f64>) -> Point2<f64> {
+        let theta = (-point.y).clamp(-1., 1.).acos();
+        let phi = azimuth(point);
+
+        Point2::new(phi / (2. * PI), theta / PI)
+    }
 }
 
 impl Hittable for Sphere {
@@ -48,8 +60,25 @@ impl Hittable for Sphere {
             let direction = ray.direction();
             let point = ray.at(t);
             let normal = Unit::new_normalize((point - self.center) / self.radius);
+            let uv = Self::uv_at(&normal);
 
-            Some(Hit::new(direction, point, t, normal, self.material.clone()))
+            Some(Hit::new(
+                direction,
+                point,
+                t,
+                normal,
+                uv,
+                self.material.clone(),
+            ))
         }
     }
+
+    fn bounding_box(&self) -> Aabb {
+        let radius_vector = Vector3::new(self.radius, self.radius, self.radius);
+
+        Aabb::from_points(
+            &(self.center - radius_vector),
+            &(self.center + radius_vector),
+        )
+    }
 }
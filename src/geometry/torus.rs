@@ -0,0 +1,101 @@
+use std::f64::consts::TAU;
+
+use crate::geometry::aabb::Aabb;
+use crate::geometry::interval::Interval;
+use crate::geometry::polynomial::quartic_roots;
+use crate::geometry::ray::{Hit, Hittable, Ray};
+use crate::materials::Material;
+use crate::util::azimuth;
+use na::{Point2, Point3, Unit, Vector3};
+
+/// A torus lying flat around `center`, with its hole along the y axis.
+///
+/// The texture coordinates go around the hole in `u`, and around the tube in `v`.
+#[derive(Clone)]
+pub struct Torus {
+    pub center: Point3<f64>,
+    /// The distance from the center to the middle of the tube.
+    pub major_radius: f64,
+    /// The radius of the tube.
+    pub minor_radius: f64,
+    pub material: Box<dyn Material>,
+}
+
+impl Torus {
+    #[allow(unused)]
+    pub fn new(
+        center: Point3<f64>,
+        major_radius: f64,
+        minor_radius: f64,
+        material: impl Material + 'static,
+    ) -> Self {
+        Self {
+            center,
+            major_radius,
+            minor_radius,
+            material: Box::new(material),
+        }
+    }
+}
+
+impl Hittable for Torus {
+    fn hits(&self, ray: &Ray, t_interval: Interval) -> Option<Hit> {
+        let direction = ray.direction();
+
+        // Start the ray as close to the torus as it gets, which keeps the quartic's
+        // coefficients small and the roots accurate when the ray comes from far away.
+        let start = (-(ray.origin() - self.center).dot(direction) / direction.magnitude_squared())
+            .max(t_interval.min);
+        let origin = ray.origin() + start * direction - self.center;
+
+        // Points on the torus satisfy (|p|² + R² - r²)² = 4R² (x² + z²), which is a quartic
+        // along the ray.
+        let major_squared = self.major_radius * self.major_radius;
+        let d_d = direction.magnitude_squared();
+        let o_d = origin.dot(direction);
+        let k = origin.magnitude_squared() + major_squared - self.minor_radius * self.minor_radius;
+        let coefficients = [
+            d_d * d_d,
+            4. * d_d * o_d,
+            4. * o_d * o_d + 2. * d_d * k
+                - 4. * major_squared * (direction.x * direction.x + direction.z * direction.z),
+            4. * o_d * k - 8. * major_squared * (origin.x * direction.x + origin.z * direction.z),
+            k * k - 4. * major_squared * (origin.x * origin.x + origin.z * origin.z),
+        ];
+
+        let t = quartic_roots(coefficients)
+            .into_iter()
+            .map(|t| t + start)
+            .find(|&t| t_interval.surrounds(t))?;
+
+        let point = ray.at(t);
+        let local = point - self.center;
+        let radial = Vector3::new(local.x, 0., local.z);
+        let ring = if radial.magnitude_squared() > 0. {
+            radial.normalize() * self.major_radius
+        } else {
+            Vector3::zeros()
+        };
+        let tube = local - ring;
+        let uv = Point2::new(
+            azimuth(&local) / TAU,
+            (f64::atan2(tube.y, radial.magnitude() - self.major_radius) / TAU).rem_euclid(1.),
+        );
+
+        Some(Hit::new(
+            direction,
+            point,
+            t,
+            Unit::new_normalize(tube),
+            uv,
+            self.material.clone(),
+        ))
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let outer = self.major_radius + self.minor_radius;
+        let extent = Vector3::new(outer, self.minor_radius, outer);
+
+        Aabb::from_points(&(self.center - extent), &(self.center + extent))
+    }
+}
@@ -0,0 +1,103 @@
+use crate::geometry::aabb::Aabb;
+use crate::geometry::interval::Interval;
+use crate::geometry::ray::{Hit, Hittable, Ray, Span};
+use na::{Affine3, Matrix3, Point3, Unit};
+
+/// An object placed in the world by an affine transform, which can move, rotate, scale
+/// and shear it.
+///
+/// Rays are mapped into the object's own space to be tested against it, so the object
+/// itself is never changed. Transforming an [`Arc`](std::sync::Arc) of an object makes an instance of it,
+/// so that it can be placed any number of times without being copied.
+pub struct Transformed<H: Hittable> {
+    object: H,
+    /// The transform from the object's space into the world.
+    transform: Affine3<f64>,
+    inverse: Affine3<f64>,
+    /// The matrix that carries normals into the world, which is the inverse transpose of
+    /// the transform's linear part.
+    normal_matrix: Matrix3<f64>,
+    bounding_box: Aabb,
+}
+
+impl<H: Hittable> Transformed<H> {
+    /// Place `object` in the world with `transform`, which has to be invertible.
+    pub fn new(object: H, transform: Affine3<f64>) -> Self {
+        let inverse = transform.inverse();
+        let normal_matrix = inverse.matrix().fixed_view::<3, 3>(0, 0).transpose();
+
+        // The transformed box is enclosed by the transforms of the original's corners.
+        let object_box = object.bounding_box();
+        let bounding_box = if object_box.is_empty() {
+            Aabb::EMPTY
+        } else if !object_box.is_bounded() {
+            // Transforming infinite corners would give NaNs, and the result is infinite anyway.
+            Aabb::UNIVERSE
+        } else {
+            (0..8).fold(Aabb::EMPTY, |acc, corner: usize| {
+                let pick = |axis: usize| {
+                    let extent = object_box.axis(axis);
+                    if corner & (1 << axis) == 0 {
+                        extent.min
+                    } else {
+                        extent.max
+                    }
+                };
+                let point = transform.transform_point(&Point3::new(pick(0), pick(1), pick(2)));
+
+                Aabb::enclosing(&acc, &Aabb::from_points(&point, &point))
+            })
+        };
+
+        Self {
+            object,
+            transform,
+            inverse,
+            normal_matrix,
+            bounding_box,
+        }
+    }
+
+    /// Carry `ray` into the object's space.
+    fn object_ray(&self, ray: &Ray) -> Ray {
+        // The direction isn't normalized afterwards, so distances along the ray are the same
+        // in both spaces.
+        Ray::new(
+            self.inverse.transform_point(ray.origin()),
+            self.inverse.transform_vector(ray.direction()),
+            ray.time(),
+        )
+    }
+
+    /// Carry a hit on the object back out into the world.
+    fn hit_in_world(&self, mut hit: Hit) -> Hit {
+        hit.point = self.transform.transform_point(&hit.point);
+        // The normal already faces against the ray, and an affine transform keeps it that way.
+        hit.normal = Unit::new_normalize(self.normal_matrix * hit.normal.into_inner());
+
+        hit
+    }
+}
+
+impl<H: Hittable> Hittable for Transformed<H> {
+    fn hits(&self, ray: &Ray, t_interval: Interval) -> Option<Hit> {
+        let hit = self.object.hits(&self.object_ray(ray), t_interval)?;
+
+        Some(self.hit_in_world(hit))
+    }
+
+    fn spans(&self, ray: &Ray, t_interval: Interval) -> Vec<Span> {
+        self.object
+            .spans(&self.object_ray(ray), t_interval)
+            .into_iter()
+            .map(|span| Span {
+                entry: self.hit_in_world(span.entry),
+                exit: self.hit_in_world(span.exit),
+            })
+            .collect()
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bounding_box
+    }
+}
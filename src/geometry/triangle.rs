@@ -0,0 +1,149 @@
+use crate::geometry::aabb::Aabb;
+use crate::geometry::interval::Interval;
+use crate::geometry::ray::{Hit, Hittable, Ray};
+use crate::materials::Material;
+use na::{Point2, Point3, Unit, Vector3};
+
+/// A single triangle, for when a whole [`Mesh`](crate::geometry::mesh::Mesh) is overkill.
+#[derive(Clone)]
+pub struct Triangle {
+    pub vertices: [Point3<f64>; 3],
+    /// Per-vertex normals, interpolated across the face for smooth shading.
+    pub normals: Option<[Unit<Vector3<f64>>; 3]>,
+    /// Per-vertex texture coordinates.
+    pub uvs: Option<[Point2<f64>; 3]>,
+    pub material: Box<dyn Material>,
+}
+
+impl Triangle {
+    #[allow(unused)]
+    pub fn new(vertices: [Point3<f64>; 3], material: impl Material + 'static) -> Self {
+        Self {
+            vertices,
+            normals: None,
+            uvs: None,
+            material: Box::new(material),
+        }
+    }
+}
+
+impl Hittable for Triangle {
+    fn hits(&self, ray: &Ray, t_interval: Interval) -> Option<Hit> {
+        let intersection = intersect(ray, &self.vertices, t_interval)?;
+
+        Some(intersection.into_hit(
+            ray,
+            self.normals.as_ref(),
+            self.uvs.as_ref(),
+            self.material.clone(),
+        ))
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        bounding_box(&self.vertices)
+    }
+}
+
+/// Where a ray crosses a triangle.
+pub(crate) struct TriangleIntersection {
+    pub t: f64,
+    /// The barycentric coordinates of the intersection, one weight per vertex.
+    pub barycentric: [f64; 3],
+    pub geometric_normal: Unit<Vector3<f64>>,
+}
+
+impl TriangleIntersection {
+    /// Build a [`Hit`], using the interpolated vertex normals for shading if there are any.
+    ///
+    /// Without vertex texture coordinates, the triangle's own barycentric coordinates are
+    /// used instead.
+    pub fn into_hit(
+        self,
+        ray: &Ray,
+        vertex_normals: Option<&[Unit<Vector3<f64>>; 3]>,
+        vertex_uvs: Option<&[Point2<f64>; 3]>,
+        material: Box<dyn Material>,
+    ) -> Hit {
+        let [w0, w1, w2] = self.barycentric;
+        let point = ray.at(self.t);
+        let uv = match vertex_uvs {
+            Some([uv0, uv1, uv2]) => {
+                Point2::from(w0 * uv0.coords + w1 * uv1.coords + w2 * uv2.coords)
+            }
+            None => Point2::new(w1, w2),
+        };
+        let mut hit = Hit::new(
+            ray.direction(),
+            point,
+            self.t,
+            self.geometric_normal,
+            uv,
+            material,
+        );
+
+        if let Some(normals) = vertex_normals {
+            let shading_normal = Unit::new_normalize(
+                w0 * normals[0].into_inner()
+                    + w1 * normals[1].into_inner()
+                    + w2 * normals[2].into_inner(),
+            );
+
+            // Whether the ray hit the front face is decided by the true surface, so the
+            // shading normal only gets to bend the normal within that hemisphere.
+            hit.normal = if shading_normal.dot(&hit.normal) < 0. {
+                -shading_normal
+            } else {
+                shading_normal
+            };
+        }
+
+        hit
+    }
+}
+
+/// Intersect a ray with the triangle with the given vertices, using the Möller–Trumbore
+/// algorithm.
+pub(crate) fn intersect(
+    ray: &Ray,
+    [p0, p1, p2]: &[Point3<f64>; 3],
+    t_interval: Interval,
+) -> Option<TriangleIntersection> {
+    let direction = ray.direction();
+    let edge_1 = p1 - p0;
+    let edge_2 = p2 - p0;
+
+    let p = direction.cross(&edge_2);
+    let determinant = edge_1.dot(&p);
+    if determinant.abs() < 1e-12 {
+        // The ray is parallel to the triangle.
+        return None;
+    }
+    let inverse_determinant = 1. / determinant;
+
+    let s = ray.origin() - p0;
+    let u = s.dot(&p) * inverse_determinant;
+    if !(0. ..=1.).contains(&u) {
+        return None;
+    }
+
+    let q = s.cross(&edge_1);
+    let v = direction.dot(&q) * inverse_determinant;
+    if v < 0. || u + v > 1. {
+        return None;
+    }
+
+    let t = edge_2.dot(&q) * inverse_determinant;
+    if !t_interval.surrounds(t) {
+        return None;
+    }
+
+    Some(TriangleIntersection {
+        t,
+        barycentric: [1. - u - v, u, v],
+        geometric_normal: Unit::new_normalize(edge_1.cross(&edge_2)),
+    })
+}
+
+pub(crate) fn bounding_box([p0, p1, p2]: &[Point3<f64>; 3]) -> Aabb {
+    Aabb::enclosing(&Aabb::from_points(p0, p1), &Aabb::from_points(p1, p2))
+}
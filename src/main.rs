@@ -1,34 +1,73 @@
 extern crate nalgebra as na;
 
-use std::f64::consts::TAU;
 use mimalloc::MiMalloc;
 
 #[global_allocator]
 static GLOBAL: MiMalloc = MiMalloc;
 
 mod camera;
-use crate::camera::Camera;
+
+mod cli;
+use crate::cli::Args;
+
+mod distributed;
+
+mod environments;
+
+mod film;
+use crate::film::Film;
+
+mod filter;
 
 mod geometry;
-use crate::geometry::ray::Hittable;
-use crate::geometry::sphere::Sphere;
+use crate::geometry::bvh::Bvh;
 
 mod materials;
-use crate::materials::{Dielectric, Lambertian, Metal};
+
+mod output;
+
+mod samplers;
+
+mod scene;
+use crate::scene::{builtin, Scene};
+
+mod textures;
+
+mod tiles;
+
+mod tonemap;
 
 mod util;
-use crate::util::{color, random_color};
+use crate::util::SampleRng;
 
-use anyhow::Result;
-use na::Point3;
-use std::fs;
-use std::path::{Path, PathBuf};
+mod video;
+use crate::video::Video;
+
+use anyhow::{bail, Context, Result};
+use clap::Parser;
 use indicatif::ProgressIterator;
+use rand::SeedableRng;
+use std::fs;
+use std::mem;
+use std::time::{Duration, Instant};
 
 fn main() -> Result<()> {
-    let width = 1200;
-    let height = 675;
+    let args = Args::parse();
+
+    if let Some(threads) = args.threads {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build_global()?;
+    }
 
+    if let Some(coordinator) = &args.worker {
+        return distributed::work(coordinator, |seed| load_scene(&args, seed));
+    }
+
+    let seed = args.seed.unwrap_or(0);
+    let mut scene = load_scene(&args, seed)?;
+
+    let (width, height) = scene.camera.image_size;
     println!(
         "Resolution = {}x{}, aspect ratio = {}",
         width,
@@ -36,105 +75,209 @@ fn main() -> Result<()> {
         readable_aspect_ratio(width, height)
     );
 
-    let mut world: Vec<Box<dyn Hittable>> = Vec::new();
-
-    let material_ground = Lambertian::new(color(0.5, 0.5, 0.5));
-    world.push(Box::new(Sphere::new(
-        Point3::new(0., -1000., 0.),
-        1000.,
-        material_ground,
-    )));
-
-    for a in -11..11 {
-        for b in -11..11 {
-            let a = f64::from(a);
-            let b = f64::from(b);
-
-            let choose_mat = rand::random::<f64>();
-            let center = Point3::new(
-                a + 0.9 * rand::random::<f64>(),
-                0.2,
-                b + 0.9 * rand::random::<f64>(),
-            );
-
-            if (center - Point3::new(4., 0.2, 0.)).magnitude() > 0.9 {
-                if choose_mat < 0.8 {
-                    let albedo = random_color();
-                    let material = Lambertian::new(albedo);
-
-                    world.push(Box::new(Sphere::new(center, 0.2, material)));
-                } else if choose_mat < 0.95 {
-                    let albedo = color(
-                        0.5 * rand::random::<f64>() + 0.5,
-                        0.5 * rand::random::<f64>() + 0.5,
-                        0.5 * rand::random::<f64>() + 0.5,
-                    );
-                    let fuzz = 0.5 * rand::random::<f64>() + 0.5;
-                    let material = Metal::new(albedo, fuzz);
-
-                    world.push(Box::new(Sphere::new(center, 0.2, material)));
-                } else {
-                    // Glass
-                    let material = Dielectric::new(1.5);
-
-                    world.push(Box::new(Sphere::new(center, 0.2, material)));
+    let output_dir = &args.output;
+    fs::create_dir_all(output_dir)?;
+    let frame_path = |frame: u32, extension: &str| {
+        output_dir.join(format!(
+            "{}.{extension}",
+            output::frame_name(frame, scene.frames)
+        ))
+    };
+    let output_path = |frame: u32| frame_path(frame, args.format.extension());
+
+    let frames = match args.frames {
+        Some(frames) => frames.clamp_to(scene.frames)?,
+        None => 1..scene.frames + 1,
+    };
+
+    let mut video = match &args.video {
+        Some(path) => Some(Video::create(
+            path,
+            scene.camera.image_size,
+            frames.start,
+            frames.len() as u32,
+            args.frame_rate,
+            args.codec.as_deref(),
+        )?),
+        None => None,
+    };
+    // Frames finished by an earlier run still have to go in the video.
+    let finished_frame =
+        |frame: u32| output::load_display(&output_path(frame), args.format, &scene.tone_mapper);
+
+    if let Some(address) = &args.coordinator {
+        // Distributed renders don't checkpoint, so only whole frames can be resumed.
+        let mut remaining_frames = Vec::new();
+        for frame in frames {
+            if args.resume && output_path(frame).exists() {
+                if let Some(video) = &mut video {
+                    video.add_frame(frame, finished_frame(frame)?)?;
                 }
+            } else {
+                remaining_frames.push(frame);
             }
         }
+
+        let worker_timeout = Duration::from_secs_f64(args.worker_timeout);
+        distributed::coordinate(
+            address,
+            &scene,
+            seed,
+            worker_timeout,
+            &remaining_frames,
+            |frame, film| {
+                let image = film.image();
+                output::save(&image, &output_path(frame), args.format, &scene.tone_mapper)?;
+
+                if let Some(video) = &mut video {
+                    video.add_frame(frame, scene.tone_mapper.map_image(&image))?;
+                }
+                Ok(())
+            },
+        )?;
+
+        return video.map_or(Ok(()), Video::finish);
     }
 
-    let material_1 = Dielectric::new(1.5);
-    world.push(Box::new(Sphere::new(
-        Point3::new(0., 1., 0.),
-        1.,
-        material_1,
-    )));
-
-    let material_2 = Lambertian::new(color(0.4, 0.2, 0.1));
-    world.push(Box::new(Sphere::new(
-        Point3::new(-4., 1., 0.),
-        1.,
-        material_2,
-    )));
-
-    let material_3 = Metal::new(color(0.7, 0.6, 0.5), 0.0);
-    world.push(Box::new(Sphere::new(
-        Point3::new(4., 1., 0.),
-        1.,
-        material_3,
-    )));
-
-    let output_dir = Path::new("./output");
-    fs::create_dir_all(output_dir)?;
+    let world = Bvh::new(mem::take(&mut scene.world));
+
+    let checkpoint_interval = Duration::from_secs_f64(args.checkpoint_interval);
+
+    for frame in frames.progress() {
+        let output_file = output_path(frame);
+        let checkpoint_file = frame_path(frame, "checkpoint");
+
+        // Frames get a checkpoint as soon as they're started, so a finished image without
+        // one means the frame was finished, rather than just previewed.
+        if args.resume && output_file.exists() && !checkpoint_file.exists() {
+            if let Some(video) = &mut video {
+                video.add_frame(frame, finished_frame(frame)?)?;
+            }
+            continue;
+        }
+
+        let mut settings = scene.camera_for_frame(frame)?;
+        let frame_world = scene.world_for_frame(frame)?.map(|frame_world| {
+            settings.environment = frame_world.environment;
+            Bvh::new(frame_world.world)
+        });
+        let world = frame_world.as_ref().unwrap_or(&world);
+        let camera = settings.build();
+        let sampling = camera.sampling_settings();
+
+        let mut film = camera.new_film();
+        if args.resume && checkpoint_file.exists() {
+            let (checkpoint, checkpoint_sampling) = Film::load_checkpoint(&checkpoint_file)?;
+            if !checkpoint.has_layout_of(&film) {
+                bail!(
+                    "the checkpoint {} was rendered with a different resolution, filter or tiles",
+                    checkpoint_file.display()
+                );
+            }
+            if checkpoint_sampling != sampling {
+                bail!(
+                    "the checkpoint {} was rendered with a different seed, sampler or number of samples",
+                    checkpoint_file.display()
+                );
+            }
+            film = checkpoint;
+        } else {
+            film.save_checkpoint(&checkpoint_file, &sampling)?;
+        }
+
+        let mut last_checkpoint = Instant::now();
+        let film = camera.render_progressive(world, film, |film| {
+            if let Some(every) = args.preview_every {
+                if film.passes() % every.get() == 0 {
+                    output::save(&film.image(), &output_file, args.format, &scene.tone_mapper)?;
+                }
+            }
+
+            if last_checkpoint.elapsed() >= checkpoint_interval {
+                film.save_checkpoint(&checkpoint_file, &sampling)?;
+                last_checkpoint = Instant::now();
+            }
+
+            Ok(())
+        })?;
+        let image = film.image();
+        output::save(&image, &output_file, args.format, &scene.tone_mapper)?;
+        fs::remove_file(&checkpoint_file)
+            .with_context(|| format!("couldn't remove checkpoint {}", checkpoint_file.display()))?;
 
-    let num_frames: u16 = 30 * 10;
-    let radius = 178f64.sqrt();
-    let angular_freq = TAU / f64::from(num_frames);
-    let phase_offset = (3./178f64.sqrt()).asin();
+        if let Some(video) = &mut video {
+            video.add_frame(frame, scene.tone_mapper.map_image(&image))?;
+        }
+    }
 
-    for frame in (0u16 .. num_frames).progress() {
-        let mut output_file = PathBuf::from(output_dir);
-        output_file.push(format!("frame_{}.png", frame + 1));
+    video.map_or(Ok(()), Video::finish)
+}
 
-        let t = angular_freq * f64::from(frame);
-        let x = radius * (t + phase_offset).cos();
-        let z = radius * (t + phase_offset).sin();
+/// Load the scene to render, or generate the built-in one from `seed`, and apply any
+/// settings from the command line on top of it.
+fn load_scene(args: &Args, seed: u64) -> Result<Scene> {
+    let mut scene = match &args.scene {
+        Some(scene_path) => Scene::load(scene_path)?,
+        None => builtin::sphere_field(&mut SampleRng::seed_from_u64(seed)),
+    };
+    for warning in &scene.warnings {
+        eprintln!("Warning: {warning}");
+    }
 
-        let camera = Camera::builder()
-            .image_size((width, height))
-            .samples_per_pixel(10)
-            .look_from(Point3::new(x, 2., z))
-            .look_at(Point3::new(0., 0., 0.))
-            .vertical_fov(20.)
-            .defocus_angle(0.6)
-            .focus_dist(10)
-            .build();
+    if let Some(seed) = args.seed {
+        scene.camera.seed = Some(seed);
+    }
 
-        let output = camera.render(&world);
-        output.save(output_file)?;
+    if let Some(resolution) = args.resolution {
+        scene.camera.image_size = (resolution.width, resolution.height);
+    }
+    if let Some(samples) = args.samples {
+        scene.camera.samples_per_pixel = Some(samples);
+    }
+    if let Some(sampler) = args.sampler {
+        scene.camera.sampler = Some(sampler);
+    }
+    if let Some(filter) = args.filter {
+        scene.camera.filter = Some(filter);
+    }
+    if let Some(filter_radius) = args.filter_radius {
+        scene.camera.filter_radius = Some(filter_radius);
+    }
+    if let Some(tile_size) = args.tile_size {
+        scene.camera.tile_size = Some(tile_size);
+    }
+    if let Some(tile_order) = args.tile_order {
+        scene.camera.tile_order = Some(tile_order);
+    }
+    if let Some(min_samples) = args.min_samples {
+        scene.camera.min_samples_per_pixel = Some(min_samples);
+    }
+    if let Some(time_limit) = args.time_limit {
+        scene.camera.time_limit = Some(time_limit);
+    }
+    if let Some(noise_threshold) = args.noise_threshold {
+        scene.camera.noise_threshold = Some(noise_threshold);
+    }
+    if let Some(shutter_open) = args.shutter_open {
+        scene.camera.shutter_open = Some(shutter_open);
+    }
+    if let Some(shutter_close) = args.shutter_close {
+        scene.camera.shutter_close = Some(shutter_close);
+    }
+    if let Some(max_depth) = args.max_depth {
+        scene.camera.max_depth = Some(max_depth);
+    }
+    if let Some(tone_mapping) = args.tone_mapping {
+        scene.tone_mapper.tone_mapping = tone_mapping;
+    }
+    if let Some(exposure) = args.exposure {
+        scene.tone_mapper.exposure = exposure;
+    }
+    if let Some(white_point) = args.white_point {
+        scene.tone_mapper.white_point = white_point;
     }
 
-    Ok(())
+    Ok(scene)
 }
 
 fn readable_aspect_ratio(width: u32, height: u32) -> String {
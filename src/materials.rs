@@ -1,4 +1,5 @@
 mod dielectric;
+mod diffuse_light;
 mod lambertian;
 mod material;
 mod metal;
@@ -6,6 +7,8 @@ mod metal;
 #[allow(unused_imports)]
 pub use dielectric::*;
 #[allow(unused_imports)]
+pub use diffuse_light::*;
+#[allow(unused_imports)]
 pub use lambertian::*;
 #[allow(unused_imports)]
 pub use material::*;
@@ -1,5 +1,6 @@
 use crate::geometry::ray::{Hit, Ray};
 use crate::materials::{Material, Scattered};
+use crate::samplers::Sampler;
 use crate::util::{color, reflect_vector};
 use na::Vector3;
 
@@ -25,7 +26,7 @@ impl Dielectric {
 }
 
 impl Material for Dielectric {
-    fn scatter(&self, ray: &Ray, hit: &Hit) -> Option<Scattered> {
+    fn scatter(&self, ray: &Ray, hit: &Hit, _sampler: &mut dyn Sampler) -> Option<Scattered> {
         let attenuation = self.tint;
         let refractive_index = if hit.front_face {
             1. / self.refractive_index
@@ -45,7 +46,7 @@ impl Material for Dielectric {
             refract_vector(&unit_direction, &hit.normal, refractive_index)
         };
 
-        let scatter_ray = Ray::new(hit.point, ray_direction);
+        let scatter_ray = Ray::new(hit.point, ray_direction, ray.time());
 
         Some(Scattered {
             attenuation,
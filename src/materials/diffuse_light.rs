@@ -0,0 +1,28 @@
+use crate::geometry::ray::{Hit, Ray};
+use crate::materials::{Material, Scattered};
+use crate::samplers::Sampler;
+use na::Vector3;
+
+/// A material that emits light evenly in every direction, and doesn't reflect any.
+#[derive(Copy, Clone, Debug)]
+pub struct DiffuseLight {
+    color: Vector3<f64>,
+    intensity: f64,
+}
+
+impl DiffuseLight {
+    #[allow(unused)]
+    pub fn new(color: Vector3<f64>, intensity: f64) -> Self {
+        DiffuseLight { color, intensity }
+    }
+}
+
+impl Material for DiffuseLight {
+    fn scatter(&self, _ray: &Ray, _hit: &Hit, _sampler: &mut dyn Sampler) -> Option<Scattered> {
+        None
+    }
+
+    fn emitted(&self, _ray: &Ray, _hit: &Hit) -> Vector3<f64> {
+        self.intensity * self.color
+    }
+}
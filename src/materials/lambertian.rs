@@ -1,31 +1,48 @@
+use std::sync::Arc;
+
 use crate::geometry::ray::{Hit, Ray};
 use crate::materials::{Material, Scattered};
-use crate::util::random_unit_vector;
+use crate::samplers::Sampler;
+use crate::textures::{SolidColor, Texture};
+use crate::util::sample_unit_vector;
 use na::Vector3;
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Clone, Debug)]
 pub struct Lambertian {
-    albedo: Vector3<f64>,
+    albedo: Arc<dyn Texture>,
 }
 
 impl Lambertian {
     #[allow(unused)]
     pub fn new(albedo: Vector3<f64>) -> Self {
+        Self::with_texture(Arc::new(SolidColor::new(albedo)))
+    }
+
+    #[allow(unused)]
+    pub fn with_texture(albedo: Arc<dyn Texture>) -> Self {
         Lambertian { albedo }
     }
 }
 
 impl Material for Lambertian {
-    fn scatter(&self, _ray: &Ray, Hit { point, normal, .. }: &Hit) -> Option<Scattered> {
-        let mut scatter_direction = normal.into_inner() + random_unit_vector().into_inner();
+    fn scatter(
+        &self,
+        ray: &Ray,
+        Hit {
+            point, normal, uv, ..
+        }: &Hit,
+        sampler: &mut dyn Sampler,
+    ) -> Option<Scattered> {
+        let mut scatter_direction =
+            normal.into_inner() + sample_unit_vector(sampler.next_2d()).into_inner();
         if scatter_direction.magnitude_squared() < 1e-8 {
             scatter_direction = normal.into_inner();
         }
 
-        let scatter_ray = Ray::new(*point, scatter_direction);
+        let scatter_ray = Ray::new(*point, scatter_direction, ray.time());
 
         Some(Scattered {
-            attenuation: self.albedo,
+            attenuation: self.albedo.value(uv, point),
             scatter_ray,
         })
     }
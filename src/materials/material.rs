@@ -1,9 +1,15 @@
 use crate::geometry::ray::{Hit, Ray};
+use crate::samplers::Sampler;
 use dyn_clone::DynClone;
 use na::Vector3;
 
 pub trait Material: Send + Sync + DynClone {
-    fn scatter(&self, ray: &Ray, hit: &Hit) -> Option<Scattered>;
+    fn scatter(&self, ray: &Ray, hit: &Hit, sampler: &mut dyn Sampler) -> Option<Scattered>;
+
+    /// The light given off by the material at the hit point, which is none by default.
+    fn emitted(&self, _ray: &Ray, _hit: &Hit) -> Vector3<f64> {
+        Vector3::zeros()
+    }
 }
 
 dyn_clone::clone_trait_object!(Material);
@@ -1,31 +1,47 @@
+use std::sync::Arc;
+
 use crate::geometry::ray::{Hit, Ray};
 use crate::materials::{Material, Scattered};
-use crate::util::{random_unit_vector, reflect_vector};
+use crate::samplers::Sampler;
+use crate::textures::{SolidColor, Texture};
+use crate::util::{reflect_vector, sample_unit_vector};
 use na::Vector3;
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Clone, Debug)]
 pub struct Metal {
-    albedo: Vector3<f64>,
+    albedo: Arc<dyn Texture>,
     fuzz: f64,
 }
 
 impl Metal {
     #[allow(unused)]
     pub fn new(albedo: Vector3<f64>, fuzz: f64) -> Self {
+        Self::with_texture(Arc::new(SolidColor::new(albedo)), fuzz)
+    }
+
+    #[allow(unused)]
+    pub fn with_texture(albedo: Arc<dyn Texture>, fuzz: f64) -> Self {
         Metal { albedo, fuzz }
     }
 }
 
 impl Material for Metal {
-    fn scatter(&self, ray: &Ray, Hit { point, normal, .. }: &Hit) -> Option<Scattered> {
+    fn scatter(
+        &self,
+        ray: &Ray,
+        Hit {
+            point, normal, uv, ..
+        }: &Hit,
+        sampler: &mut dyn Sampler,
+    ) -> Option<Scattered> {
         let reflected = reflect_vector(ray.direction(), normal);
-        let fuzzy_reflected =
-            reflected.normalize() + (self.fuzz * random_unit_vector().into_inner());
-        let scatter_ray = Ray::new(*point, fuzzy_reflected);
+        let fuzzy_reflected = reflected.normalize()
+            + (self.fuzz * sample_unit_vector(sampler.next_2d()).into_inner());
+        let scatter_ray = Ray::new(*point, fuzzy_reflected, ray.time());
 
         if fuzzy_reflected.dot(normal) > 0. {
             Some(Scattered {
-                attenuation: self.albedo,
+                attenuation: self.albedo.value(uv, point),
                 scatter_ray,
             })
         } else {
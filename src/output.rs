@@ -0,0 +1,157 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use clap::ValueEnum;
+use image::codecs::hdr::HdrEncoder;
+use image::{imageops, ImageFormat, Rgb, Rgb32FImage, RgbImage};
+
+use crate::tonemap::ToneMapper;
+
+/// The file formats rendered images can be saved in.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// 8-bit sRGB PNG, tone mapped for display.
+    #[default]
+    Png,
+    /// Linear OpenEXR, with 32-bit float channels.
+    Exr,
+    /// Linear Radiance RGBE.
+    Hdr,
+    /// Linear portable float map.
+    Pfm,
+}
+
+impl OutputFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            OutputFormat::Png => "png",
+            OutputFormat::Exr => "exr",
+            OutputFormat::Hdr => "hdr",
+            OutputFormat::Pfm => "pfm",
+        }
+    }
+}
+
+/// The name frame number `frame` of an animation `frames` long is saved under, without an
+/// extension.
+///
+/// The frame number is padded with zeros to the same width for every frame, so that the
+/// files sort in order.
+pub fn frame_name(frame: u32, frames: u32) -> String {
+    let width = frames.to_string().len().max(4);
+    format!("frame_{frame:0width$}")
+}
+
+/// Save a linear framebuffer to `path` in the given format.
+///
+/// Only the 8-bit formats go through `tone_mapper`, and the others store the framebuffer
+/// as-is.
+pub fn save(
+    image: &Rgb32FImage,
+    path: &Path,
+    format: OutputFormat,
+    tone_mapper: &ToneMapper,
+) -> Result<()> {
+    let write = || -> Result<()> {
+        match format {
+            OutputFormat::Png => tone_mapper
+                .map_image(image)
+                .save_with_format(path, ImageFormat::Png)?,
+            OutputFormat::Exr => image.save_with_format(path, ImageFormat::OpenExr)?,
+            OutputFormat::Hdr => {
+                let file = BufWriter::new(File::create(path)?);
+                let pixels: Vec<Rgb<f32>> = image.pixels().copied().collect();
+                HdrEncoder::new(file).encode(
+                    &pixels,
+                    image.width() as usize,
+                    image.height() as usize,
+                )?;
+            }
+            OutputFormat::Pfm => write_pfm(image, &mut BufWriter::new(File::create(path)?))?,
+        }
+
+        Ok(())
+    };
+
+    write().with_context(|| format!("couldn't write {}", path.display()))
+}
+
+/// Read a frame saved by [`save`] back in, tone mapped for display if it isn't already.
+pub fn load_display(
+    path: &Path,
+    format: OutputFormat,
+    tone_mapper: &ToneMapper,
+) -> Result<RgbImage> {
+    let read = || -> Result<RgbImage> {
+        Ok(match format {
+            OutputFormat::Png => image::open(path)?.into_rgb8(),
+            OutputFormat::Exr | OutputFormat::Hdr => {
+                tone_mapper.map_image(&image::open(path)?.into_rgb32f())
+            }
+            OutputFormat::Pfm => {
+                tone_mapper.map_image(&read_pfm(&mut BufReader::new(File::open(path)?))?)
+            }
+        })
+    };
+
+    read().with_context(|| format!("couldn't read {}", path.display()))
+}
+
+/// Write an image as a color portable float map, which is stored bottom row first.
+fn write_pfm(image: &Rgb32FImage, writer: &mut impl Write) -> Result<()> {
+    // A negative scale marks the data as little-endian.
+    write!(writer, "PF\n{} {}\n-1.0\n", image.width(), image.height())?;
+
+    for row in image.rows().rev() {
+        for pixel in row {
+            for channel in pixel.0 {
+                writer.write_all(&channel.to_le_bytes())?;
+            }
+        }
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+/// Read a color portable float map, like the ones [`write_pfm`] writes.
+fn read_pfm(reader: &mut impl BufRead) -> Result<Rgb32FImage> {
+    let mut line = || -> Result<String> {
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        Ok(line.trim().to_owned())
+    };
+
+    if line()? != "PF" {
+        bail!("not a color portable float map");
+    }
+    let size = line()?;
+    let (width, height): (u32, u32) = size
+        .split_once(' ')
+        .and_then(|(width, height)| Some((width.parse().ok()?, height.trim().parse().ok()?)))
+        .with_context(|| format!("`{size}` isn't an image size"))?;
+    // The sign of the scale gives the byte order, and its size doesn't matter to us.
+    let little_endian = line()?.parse::<f32>().context("the scale isn't a number")? < 0.;
+
+    let mut data = vec![0; width as usize * height as usize * 3 * 4];
+    reader.read_exact(&mut data)?;
+    let channels = data
+        .chunks_exact(4)
+        .map(|bytes| {
+            let bytes = bytes.try_into().expect("the chunks are four bytes long");
+            if little_endian {
+                f32::from_le_bytes(bytes)
+            } else {
+                f32::from_be_bytes(bytes)
+            }
+        })
+        .collect();
+
+    let mut image = Rgb32FImage::from_raw(width, height, channels)
+        .expect("there's a pixel's worth of channels for every pixel");
+    // The rows are stored bottom first.
+    imageops::flip_vertical_in_place(&mut image);
+    Ok(image)
+}
@@ -0,0 +1,16 @@
+mod halton;
+mod independent;
+mod sampler;
+mod sobol;
+mod stratified;
+
+#[allow(unused_imports)]
+pub use halton::*;
+#[allow(unused_imports)]
+pub use independent::*;
+#[allow(unused_imports)]
+pub use sampler::*;
+#[allow(unused_imports)]
+pub use sobol::*;
+#[allow(unused_imports)]
+pub use stratified::*;
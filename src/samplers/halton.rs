@@ -0,0 +1,67 @@
+use na::Point2;
+use rand::Rng;
+
+use crate::samplers::{unit_float, Sampler};
+use crate::util::{pixel_seed, sample_rng, splitmix64, SampleRng};
+
+/// The bases of the Halton sequence's dimensions. Dimensions past these have too much
+/// correlation between them to be worth using, so they fall back to random numbers.
+const PRIMES: [u64; 16] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53];
+
+/// A sampler following the Halton sequence, with the radical inverse in a different prime
+/// base for each dimension.
+///
+/// Every pixel would otherwise get exactly the same points, so each dimension is shifted by
+/// a random offset per pixel, wrapping around (a Cranley-Patterson rotation).
+pub struct Halton {
+    sample: u64,
+    pixel_seed: u64,
+    dimension: usize,
+    /// Where the dimensions past the end of [`PRIMES`] come from.
+    rng: SampleRng,
+}
+
+impl Halton {
+    pub fn new(seed: u64, x: u32, y: u32, sample: u64) -> Self {
+        Self {
+            sample,
+            pixel_seed: pixel_seed(seed, x, y),
+            dimension: 0,
+            rng: sample_rng(seed, x, y, sample),
+        }
+    }
+}
+
+impl Sampler for Halton {
+    fn next_1d(&mut self) -> f64 {
+        let Some(&base) = PRIMES.get(self.dimension) else {
+            return self.rng.gen();
+        };
+
+        #[allow(clippy::cast_possible_truncation)]
+        let offset = unit_float(splitmix64(self.pixel_seed ^ self.dimension as u64) as u32);
+        self.dimension += 1;
+
+        (radical_inverse(base, self.sample) + offset).fract()
+    }
+
+    fn next_2d(&mut self) -> Point2<f64> {
+        Point2::new(self.next_1d(), self.next_1d())
+    }
+}
+
+/// Mirror the digits of `index` in the given base around the radix point.
+#[allow(clippy::cast_precision_loss)]
+fn radical_inverse(base: u64, mut index: u64) -> f64 {
+    let inverse_base = 1. / base as f64;
+    let mut digit_weight = inverse_base;
+    let mut result = 0.;
+
+    while index > 0 {
+        result += (index % base) as f64 * digit_weight;
+        index /= base;
+        digit_weight *= inverse_base;
+    }
+
+    result
+}
@@ -0,0 +1,28 @@
+use na::Point2;
+use rand::Rng;
+
+use crate::samplers::Sampler;
+use crate::util::{sample_rng, SampleRng};
+
+/// A sampler using independent uniform random numbers for every dimension.
+pub struct Independent {
+    rng: SampleRng,
+}
+
+impl Independent {
+    pub fn new(seed: u64, x: u32, y: u32, sample: u64) -> Self {
+        Self {
+            rng: sample_rng(seed, x, y, sample),
+        }
+    }
+}
+
+impl Sampler for Independent {
+    fn next_1d(&mut self) -> f64 {
+        self.rng.gen()
+    }
+
+    fn next_2d(&mut self) -> Point2<f64> {
+        Point2::new(self.rng.gen(), self.rng.gen())
+    }
+}
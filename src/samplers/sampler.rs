@@ -0,0 +1,37 @@
+use clap::ValueEnum;
+use na::Point2;
+use serde::{Deserialize, Serialize};
+
+/// A source of sample values for one sample of one pixel.
+///
+/// Each call moves on to the next dimension of the sample, so the values for the pixel
+/// offset, the lens and every bounce each come from their own dimensions. Samplers are
+/// free to correlate a dimension across a pixel's samples to spread them out more evenly
+/// than independent random numbers would.
+pub trait Sampler {
+    /// Get the next dimension, in [0, 1).
+    fn next_1d(&mut self) -> f64;
+
+    /// Get the next two dimensions, in [0, 1)².
+    fn next_2d(&mut self) -> Point2<f64>;
+}
+
+/// The kinds of [`Sampler`] a camera can render with.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SamplerKind {
+    /// Independent uniform random numbers.
+    #[default]
+    Independent,
+    /// Jittered samples, one in each cell of a grid over the pixel's samples.
+    Stratified,
+    /// The Halton sequence, randomized per pixel.
+    Halton,
+    /// The Sobol sequence, with hash-based Owen scrambling.
+    Sobol,
+}
+
+/// Convert 32 random bits to a float in [0, 1).
+pub(crate) fn unit_float(bits: u32) -> f64 {
+    f64::from(bits) * (1. / 4_294_967_296.)
+}
@@ -0,0 +1,105 @@
+use na::Point2;
+
+use crate::samplers::{unit_float, Sampler};
+use crate::util::{pixel_seed, splitmix64};
+
+/// A sampler following the first two dimensions of the Sobol sequence, with hash-based Owen
+/// scrambling, as described in Brent Burley's _Practical Hash-based Owen Scrambling_.
+///
+/// Rather than going further into the sequence for more dimensions, every request gets its
+/// own shuffle of the sample order and scramble of the points. Each request is then a well
+/// stratified set of points on its own, without any correlation to the others.
+pub struct Sobol {
+    sample: u32,
+    pixel_seed: u64,
+    dimension: u64,
+}
+
+impl Sobol {
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn new(seed: u64, x: u32, y: u32, sample: u64) -> Self {
+        Self {
+            sample: sample as u32,
+            pixel_seed: pixel_seed(seed, x, y),
+            dimension: 0,
+        }
+    }
+
+    /// Get the seed for the next request, and move on to the one after.
+    #[allow(clippy::cast_possible_truncation)]
+    fn next_seed(&mut self) -> u32 {
+        let seed = splitmix64(self.pixel_seed ^ self.dimension) as u32;
+        self.dimension += 1;
+
+        seed
+    }
+
+    /// The scrambled `dimension`th coordinate of this sample's shuffled point.
+    fn coordinate(&self, seed: u32, dimension: u32) -> f64 {
+        let index = nested_uniform_scramble(self.sample, seed);
+        let dimension_seed = hash_combine(seed, dimension);
+
+        unit_float(nested_uniform_scramble(
+            sobol(index, dimension),
+            dimension_seed,
+        ))
+    }
+}
+
+impl Sampler for Sobol {
+    fn next_1d(&mut self) -> f64 {
+        let seed = self.next_seed();
+        self.coordinate(seed, 0)
+    }
+
+    fn next_2d(&mut self) -> Point2<f64> {
+        let seed = self.next_seed();
+        Point2::new(self.coordinate(seed, 0), self.coordinate(seed, 1))
+    }
+}
+
+/// The `dimension`th coordinate of the `index`th point of the Sobol sequence, as a 32-bit
+/// fixed point fraction. Only the first two dimensions are supported.
+fn sobol(index: u32, dimension: u32) -> u32 {
+    match dimension {
+        // The first dimension is the van der Corput sequence.
+        0 => index.reverse_bits(),
+        1 => {
+            let mut result = 0;
+            let mut direction = 1 << 31;
+
+            for bit in 0..32 {
+                if index & (1 << bit) != 0 {
+                    result ^= direction;
+                }
+                direction ^= direction >> 1;
+            }
+
+            result
+        }
+        _ => unreachable!("only the first two Sobol dimensions are implemented"),
+    }
+}
+
+/// Owen scramble the bits of `x`, flipping each bit based on a hash of the bits above it.
+fn nested_uniform_scramble(x: u32, seed: u32) -> u32 {
+    laine_karras_permutation(x.reverse_bits(), seed).reverse_bits()
+}
+
+/// A hash where every bit only depends on the bits below it, which makes it an Owen
+/// scramble when applied to reversed bits.
+fn laine_karras_permutation(mut x: u32, seed: u32) -> u32 {
+    x = x.wrapping_add(seed);
+    x ^= x.wrapping_mul(0x6c50_b47c);
+    x ^= x.wrapping_mul(0xb82f_1e52);
+    x ^= x.wrapping_mul(0xc7af_e638);
+    x ^= x.wrapping_mul(0x8d22_f6e6);
+    x
+}
+
+fn hash_combine(seed: u32, value: u32) -> u32 {
+    seed ^ (value
+        .wrapping_add(0x9e37_79b9)
+        .wrapping_add(seed << 6)
+        .wrapping_add(seed >> 2))
+}
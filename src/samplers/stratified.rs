@@ -0,0 +1,106 @@
+use na::Point2;
+use rand::Rng;
+
+use crate::samplers::Sampler;
+use crate::util::{pixel_seed, sample_rng, splitmix64, SampleRng};
+
+/// A sampler that splits every dimension into strata, one for each of the pixel's samples,
+/// and jitters each sample within its own stratum.
+///
+/// Pairs of dimensions are stratified over the largest square grid there are enough
+/// samples for, and any samples past that start the grid over. Every dimension shuffles its
+/// strata differently, so the dimensions don't end up correlated with each other.
+pub struct Stratified {
+    /// Which of the pixel's samples this is.
+    sample: u32,
+    samples_per_pixel: u32,
+    pixel_seed: u64,
+    dimension: u64,
+    /// Where the jitter within each stratum comes from.
+    rng: SampleRng,
+}
+
+impl Stratified {
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn new(seed: u64, x: u32, y: u32, sample: u64, samples_per_pixel: usize) -> Self {
+        let samples_per_pixel = samples_per_pixel.clamp(1, u32::MAX as usize) as u32;
+
+        Self {
+            sample: (sample % u64::from(samples_per_pixel)) as u32,
+            samples_per_pixel,
+            pixel_seed: pixel_seed(seed, x, y),
+            dimension: 0,
+            rng: sample_rng(seed, x, y, sample),
+        }
+    }
+
+    /// Pick this sample's stratum out of `strata`, shuffled for the current dimension.
+    #[allow(clippy::cast_possible_truncation)]
+    fn stratum(&mut self, strata: u32) -> u32 {
+        let permutation_seed = splitmix64(self.pixel_seed ^ self.dimension) as u32;
+        self.dimension += 1;
+
+        permute(self.sample % strata, strata, permutation_seed)
+    }
+}
+
+impl Sampler for Stratified {
+    fn next_1d(&mut self) -> f64 {
+        let stratum = self.stratum(self.samples_per_pixel);
+
+        (f64::from(stratum) + self.rng.gen::<f64>()) / f64::from(self.samples_per_pixel)
+    }
+
+    fn next_2d(&mut self) -> Point2<f64> {
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let side = f64::from(self.samples_per_pixel).sqrt() as u32;
+        let cell = self.stratum(side * side);
+
+        let (cell_x, cell_y) = (cell % side, cell / side);
+        Point2::new(
+            (f64::from(cell_x) + self.rng.gen::<f64>()) / f64::from(side),
+            (f64::from(cell_y) + self.rng.gen::<f64>()) / f64::from(side),
+        )
+    }
+}
+
+/// Find where `index` goes in a pseudorandom permutation of `0..length`, chosen by `seed`.
+///
+/// This is Andrew Kensler's hash-based permutation from _Correlated Multi-Jittered
+/// Sampling_, which doesn't need the permutation to be stored anywhere.
+fn permute(mut index: u32, length: u32, seed: u32) -> u32 {
+    let mut mask = length - 1;
+    mask |= mask >> 1;
+    mask |= mask >> 2;
+    mask |= mask >> 4;
+    mask |= mask >> 8;
+    mask |= mask >> 16;
+
+    // Hash within the next power of two up, and try again until the result is in range.
+    loop {
+        index ^= seed;
+        index = index.wrapping_mul(0xe170_893d);
+        index ^= seed >> 16;
+        index ^= (index & mask) >> 4;
+        index ^= seed >> 8;
+        index = index.wrapping_mul(0x0929_eb3f);
+        index ^= seed >> 23;
+        index ^= (index & mask) >> 1;
+        index = index.wrapping_mul(1 | seed >> 27);
+        index = index.wrapping_mul(0x6935_fa69);
+        index ^= (index & mask) >> 11;
+        index = index.wrapping_mul(0x74dc_b303);
+        index ^= (index & mask) >> 2;
+        index = index.wrapping_mul(0x9e50_1cc3);
+        index ^= (index & mask) >> 2;
+        index = index.wrapping_mul(0xc860_a3df);
+        index &= mask;
+        index ^= index >> 5;
+
+        if index < length {
+            break;
+        }
+    }
+
+    index.wrapping_add(seed) % length
+}
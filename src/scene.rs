@@ -0,0 +1,233 @@
+mod animation;
+pub mod builtin;
+mod description;
+mod loader;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{bail, Context, Result};
+use toml::{Table, Value};
+
+use crate::environments::Environment;
+use crate::geometry::ray::Hittable;
+use crate::tonemap::ToneMapper;
+
+use animation::Track;
+pub use description::CameraSettings;
+use description::{SceneDescription, SceneSpans};
+use loader::{AssetCache, Loader};
+
+/// A scene loaded from a scene file, ready to be rendered.
+pub struct Scene {
+    pub camera: CameraSettings,
+    pub world: Vec<Box<dyn Hittable>>,
+    pub tone_mapper: ToneMapper,
+    /// The number of frames in the scene's animation.
+    pub frames: u32,
+    /// Problems with the scene that it can still be rendered in spite of.
+    pub warnings: Vec<String>,
+    /// The scene's animated properties.
+    tracks: Vec<Track>,
+    /// The scene file, if the animation changes anything but the camera and the world has
+    /// to be rebuilt from it for every frame.
+    source: Option<SceneSource>,
+}
+
+struct SceneSource {
+    /// The scene file's text, which error messages point into.
+    text: String,
+    document: Table,
+    spans: SceneSpans,
+    base_dir: PathBuf,
+    /// The files the scene refers to, so that they're only read once for all of the frames.
+    assets: AssetCache,
+}
+
+/// The objects in one frame of an animation that changes them.
+pub struct FrameWorld {
+    pub world: Vec<Box<dyn Hittable>>,
+    pub environment: Option<Arc<dyn Environment>>,
+}
+
+/// The parts of the scene file that tracks can animate.
+const ANIMATABLE_SECTIONS: [&str; 6] = [
+    "camera",
+    "environment",
+    "textures",
+    "materials",
+    "objects",
+    "prototypes",
+];
+
+impl Scene {
+    /// Load a scene from a TOML scene file.
+    pub fn load(path: &Path) -> Result<Self> {
+        let source = fs::read_to_string(path)
+            .with_context(|| format!("couldn't read scene file {}", path.display()))?;
+
+        let base_dir = path.parent().unwrap_or(Path::new(""));
+        Self::from_toml(&source, base_dir)
+            .with_context(|| format!("in scene file {}", path.display()))
+    }
+
+    /// Parse a scene from the contents of a TOML scene file, resolving any relative paths
+    /// in it against `base_dir`.
+    pub fn from_toml(source: &str, base_dir: &Path) -> Result<Self> {
+        let description: SceneDescription = toml::from_str(source)?;
+        let spans: SceneSpans = toml::from_str(source)?;
+        let (width, height) = description.camera.image_size;
+        if width == 0 || height == 0 {
+            bail!("the camera's image size of {width}x{height} is empty");
+        }
+        if let Some(time_limit) = description.camera.time_limit {
+            if !(time_limit > 0. && time_limit.is_finite()) {
+                bail!("the camera's time limit must be a positive number of seconds");
+            }
+        }
+        if let Some(filter_radius) = description.camera.filter_radius {
+            if !(filter_radius > 0. && filter_radius.is_finite()) {
+                bail!("the camera's filter radius must be a positive number of pixels");
+            }
+        }
+        let white_point = description.output.white_point;
+        if !(white_point > 0. && white_point.is_finite()) {
+            bail!("the output's white point must be a positive luminance");
+        }
+        let assets = AssetCache::default();
+        let mut loader = Loader::new(source, base_dir, &description, &spans, &assets);
+
+        let world = loader.world()?;
+        let mut camera = description.camera.clone();
+        camera.environment = loader.environment()?;
+        let warnings = assets.take_warnings();
+
+        let mut frames = 1;
+        let mut tracks = Vec::new();
+        if let Some(animation) = &description.animation {
+            let track_spans = spans
+                .animation
+                .as_ref()
+                .map(|animation| &animation.tracks[..])
+                .unwrap_or_default();
+
+            for (index, track) in animation.tracks.iter().enumerate() {
+                let line = loader.line_of(&track_spans[index]);
+                let track =
+                    Track::new(track).with_context(|| format!("in the track at line {line}"))?;
+
+                if !ANIMATABLE_SECTIONS.contains(&track.section()) {
+                    bail!(
+                        "the track at line {line} animates `{}`, but only the camera, \
+                         environment, textures, materials, objects and prototypes can be \
+                         animated",
+                        track.section()
+                    );
+                }
+                tracks.push(track);
+            }
+
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            let last_keyframe = tracks
+                .iter()
+                .map(Track::last_frame)
+                .fold(1., f64::max)
+                .ceil() as u32;
+            frames = animation.frames.unwrap_or(last_keyframe).max(1);
+        }
+
+        // Catch any tracks for properties that don't exist before anything gets rendered.
+        let document: Table = source.parse()?;
+        let mut animated_document = document.clone();
+        for track in &tracks {
+            track.apply(&mut animated_document, 1.)?;
+        }
+
+        let source = tracks
+            .iter()
+            .any(|track| track.section() != "camera")
+            .then(|| SceneSource {
+                text: source.to_owned(),
+                document,
+                spans,
+                base_dir: base_dir.to_owned(),
+                assets,
+            });
+
+        Ok(Self {
+            camera,
+            world,
+            tone_mapper: description.output,
+            frames,
+            warnings,
+            tracks,
+            source,
+        })
+    }
+
+    /// Get the camera settings for the frame numbered `frame`, counting from 1.
+    pub fn camera_for_frame(&self, frame: u32) -> Result<CameraSettings> {
+        let mut tracks = self
+            .tracks
+            .iter()
+            .filter(|track| track.section() == "camera")
+            .peekable();
+        if tracks.peek().is_none() {
+            return Ok(self.camera.clone());
+        }
+
+        let mut document = Table::new();
+        document.insert("camera".to_owned(), Value::try_from(&self.camera)?);
+        for track in tracks {
+            track.apply(&mut document, f64::from(frame))?;
+        }
+
+        let camera = document
+            .remove("camera")
+            .expect("the camera was just inserted");
+        let mut camera: CameraSettings = camera
+            .try_into()
+            .with_context(|| format!("the camera's animation is invalid at frame {frame}"))?;
+        camera.environment.clone_from(&self.camera.environment);
+
+        Ok(camera)
+    }
+
+    /// Rebuild the world for the frame numbered `frame`, counting from 1, if the animation
+    /// changes it. If not, [`Scene::world`] is the same for every frame.
+    pub fn world_for_frame(&self, frame: u32) -> Result<Option<FrameWorld>> {
+        let Some(source) = &self.source else {
+            return Ok(None);
+        };
+
+        let build = || -> Result<FrameWorld> {
+            let mut document = source.document.clone();
+            for track in self
+                .tracks
+                .iter()
+                .filter(|track| track.section() != "camera")
+            {
+                track.apply(&mut document, f64::from(frame))?;
+            }
+
+            let description: SceneDescription = document.try_into()?;
+            let mut loader = Loader::new(
+                &source.text,
+                &source.base_dir,
+                &description,
+                &source.spans,
+                &source.assets,
+            );
+
+            Ok(FrameWorld {
+                world: loader.world()?,
+                environment: loader.environment()?,
+            })
+        };
+
+        build()
+            .map(Some)
+            .with_context(|| format!("couldn't build the scene for frame {frame}"))
+    }
+}
@@ -0,0 +1,349 @@
+//! Keyframed properties, which the scene is rebuilt with for each frame of an animation.
+//!
+//! A track changes a single numeric property anywhere in the scene file, named by its path
+//! through the document like `camera.look_from` or `objects.2.radius`, and the scene is
+//! rendered as if the file had said that frame's value all along.
+
+use std::f64::consts::{PI, TAU};
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use toml::{Table, Value};
+
+use crate::scene::description::{TrackDescription, TrackKeyframeDescription, TrackValue};
+
+/// How a track's value moves between its keyframes.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Interpolation {
+    /// In a straight line, at a constant speed.
+    #[default]
+    Linear,
+    /// Along a cubic Bézier curve, pulled towards each keyframe's `out` handle as it leaves
+    /// it and the next one's `in` handle as it arrives. Leaving the handles out eases in and
+    /// out of every keyframe.
+    Bezier,
+    /// Along a Catmull–Rom spline, which passes smoothly through every keyframe.
+    CatmullRom,
+    /// Around the y axis, for points: turning at a constant speed, the shorter way around,
+    /// while the distance from the axis and the height change in a straight line. Keyframes
+    /// on a circle around the axis are joined by exact arcs of it.
+    Orbit,
+}
+
+/// A keyframe, with its value and handles flattened into lists of numbers.
+#[derive(Clone, Debug)]
+struct Keyframe {
+    frame: f64,
+    value: Vec<f64>,
+    in_handle: Vec<f64>,
+    out_handle: Vec<f64>,
+}
+
+/// One animated property.
+#[derive(Clone, Debug)]
+pub struct Track {
+    /// The path to the property, as keys and array indices.
+    path: Vec<PathSegment>,
+    interpolation: Interpolation,
+    /// The keyframes, sorted by frame.
+    keyframes: Vec<Keyframe>,
+    /// Whether the values are single numbers, rather than lists of them.
+    scalar: bool,
+    /// Whether the values are all integers, and should stay that way.
+    integer: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+impl Track {
+    pub fn new(description: &TrackDescription) -> Result<Self> {
+        let path = parse_path(&description.property)?;
+
+        let Some(first) = description.keyframes.first() else {
+            bail!("the track has no keyframes");
+        };
+        let shape = first.value.shape();
+        if description.interpolation == Interpolation::Orbit && shape != Some(3) {
+            bail!("only points, with three numbers each, can orbit");
+        }
+
+        let keyframes = description
+            .keyframes
+            .iter()
+            .map(|keyframe| {
+                Self::keyframe(keyframe, shape)
+                    .with_context(|| format!("in the keyframe at frame {}", keyframe.frame))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut track = Self {
+            path,
+            interpolation: description.interpolation,
+            keyframes,
+            scalar: shape.is_none(),
+            integer: description
+                .keyframes
+                .iter()
+                .all(|keyframe| keyframe.value.is_integer()),
+        };
+        track.sort_keyframes()?;
+
+        Ok(track)
+    }
+
+    /// Create a track for a list-valued property, passing through the given values.
+    pub fn through(
+        property: &str,
+        interpolation: Interpolation,
+        keyframes: impl IntoIterator<Item = (f64, Vec<f64>)>,
+    ) -> Result<Self> {
+        let keyframes: Vec<Keyframe> = keyframes
+            .into_iter()
+            .map(|(frame, value)| Keyframe {
+                frame,
+                in_handle: value.clone(),
+                out_handle: value.clone(),
+                value,
+            })
+            .collect();
+        if keyframes.is_empty() {
+            bail!("the track has no keyframes");
+        }
+        if interpolation == Interpolation::Orbit
+            && keyframes.iter().any(|keyframe| keyframe.value.len() != 3)
+        {
+            bail!("only points, with three numbers each, can orbit");
+        }
+
+        let mut track = Self {
+            path: parse_path(property)?,
+            interpolation,
+            keyframes,
+            scalar: false,
+            integer: false,
+        };
+        track.sort_keyframes()?;
+
+        Ok(track)
+    }
+
+    /// Put the keyframes in order, checking that no two of them are on the same frame.
+    fn sort_keyframes(&mut self) -> Result<()> {
+        self.keyframes.sort_by(|a, b| a.frame.total_cmp(&b.frame));
+
+        if let Some(pair) = self
+            .keyframes
+            .windows(2)
+            .find(|pair| pair[0].frame == pair[1].frame)
+        {
+            bail!("there's more than one keyframe at frame {}", pair[0].frame);
+        }
+
+        Ok(())
+    }
+
+    /// Flatten a keyframe, checking that its values have the given shape.
+    fn keyframe(description: &TrackKeyframeDescription, shape: Option<usize>) -> Result<Keyframe> {
+        if !description.frame.is_finite() {
+            bail!("keyframes have to be at a finite frame");
+        }
+        if description.value.shape() != shape {
+            bail!("every keyframe in a track has to have the same shape of value");
+        }
+
+        let value = description.value.to_vec();
+        let handle = |handle: &Option<TrackValue>| match handle {
+            Some(handle) if handle.shape() != shape => {
+                bail!("the handles have to be the same shape as the value")
+            }
+            Some(handle) => Ok(handle.to_vec()),
+            None => Ok(value.clone()),
+        };
+
+        Ok(Keyframe {
+            frame: description.frame,
+            in_handle: handle(&description.in_handle)?,
+            out_handle: handle(&description.out_handle)?,
+            value,
+        })
+    }
+
+    /// The top-level table of the scene file that the property is in.
+    pub fn section(&self) -> &str {
+        match self.path.first() {
+            Some(PathSegment::Key(key)) => key,
+            _ => "",
+        }
+    }
+
+    /// The last frame with a keyframe on it.
+    pub fn last_frame(&self) -> f64 {
+        self.keyframes.last().map_or(1., |keyframe| keyframe.frame)
+    }
+
+    /// Set the property in `document` to its value at `frame`.
+    ///
+    /// Keys missing from the end of the path are added, so that properties that are usually
+    /// left to their defaults can be animated too.
+    pub fn apply(&self, document: &mut Table, frame: f64) -> Result<()> {
+        self.apply_at(document, &self.path, frame).with_context(|| {
+            let path: Vec<String> = self
+                .path
+                .iter()
+                .map(|segment| match segment {
+                    PathSegment::Key(key) => key.clone(),
+                    PathSegment::Index(index) => index.to_string(),
+                })
+                .collect();
+            format!("couldn't animate `{}`", path.join("."))
+        })
+    }
+
+    fn apply_at(&self, table: &mut Table, path: &[PathSegment], frame: f64) -> Result<()> {
+        let Some((PathSegment::Key(key), rest)) = path.split_first() else {
+            bail!("tables can only be indexed by name");
+        };
+
+        if rest.is_empty() {
+            table.insert(key.clone(), self.value_at(frame));
+            return Ok(());
+        }
+
+        let mut value = table
+            .get_mut(key)
+            .with_context(|| format!("there's no `{key}`"))?;
+        let mut rest = rest;
+
+        // Follow any array indices down to the next table.
+        while let Some((PathSegment::Index(index), remaining)) = rest.split_first() {
+            let Value::Array(array) = value else {
+                bail!("`{key}` isn't an array");
+            };
+            let element = array
+                .get_mut(*index)
+                .with_context(|| format!("there's no element {index} in `{key}`"))?;
+
+            if remaining.is_empty() {
+                *element = self.value_at(frame);
+                return Ok(());
+            }
+            value = element;
+            rest = remaining;
+        }
+
+        let Value::Table(table) = value else {
+            bail!("`{key}` isn't a table");
+        };
+        self.apply_at(table, rest, frame)
+    }
+
+    /// The track's value at `frame`, as it would be written in the scene file.
+    fn value_at(&self, frame: f64) -> Value {
+        let value = self.interpolate(frame);
+        let number = |x: f64| {
+            if self.integer {
+                #[allow(clippy::cast_possible_truncation)]
+                Value::Integer(x.round() as i64)
+            } else {
+                Value::Float(x)
+            }
+        };
+
+        if self.scalar {
+            number(value[0])
+        } else {
+            Value::Array(value.into_iter().map(number).collect())
+        }
+    }
+
+    fn interpolate(&self, frame: f64) -> Vec<f64> {
+        let keyframes = &self.keyframes;
+        let next = keyframes.partition_point(|keyframe| keyframe.frame <= frame);
+
+        // Before the first keyframe and after the last, the value holds still.
+        if next == 0 {
+            return keyframes[0].value.clone();
+        }
+        if next == keyframes.len() {
+            return keyframes[next - 1].value.clone();
+        }
+
+        let (k0, k1) = (&keyframes[next - 1], &keyframes[next]);
+        let duration = k1.frame - k0.frame;
+        let t = (frame - k0.frame) / duration;
+
+        match self.interpolation {
+            Interpolation::Linear => combine(&[(&k0.value, 1. - t), (&k1.value, t)]),
+            Interpolation::Bezier => {
+                let s = 1. - t;
+                combine(&[
+                    (&k0.value, s * s * s),
+                    (&k0.out_handle, 3. * s * s * t),
+                    (&k1.in_handle, 3. * s * t * t),
+                    (&k1.value, t * t * t),
+                ])
+            }
+            Interpolation::CatmullRom => {
+                // The tangent at each keyframe points from the one before it to the one after,
+                // or just towards its only neighbor at either end, scaled to this segment.
+                let tangent = |i: usize| {
+                    let before = &keyframes[i.saturating_sub(1)];
+                    let after = &keyframes[(i + 1).min(keyframes.len() - 1)];
+                    let scale = duration / (after.frame - before.frame);
+                    combine(&[(&after.value, scale), (&before.value, -scale)])
+                };
+                let (m0, m1) = (tangent(next - 1), tangent(next));
+
+                let (t2, t3) = (t * t, t * t * t);
+                combine(&[
+                    (&k0.value, 2. * t3 - 3. * t2 + 1.),
+                    (&m0, t3 - 2. * t2 + t),
+                    (&k1.value, -2. * t3 + 3. * t2),
+                    (&m1, t3 - t2),
+                ])
+            }
+            Interpolation::Orbit => {
+                let (p0, p1) = (&k0.value, &k1.value);
+                let (r0, r1) = (p0[0].hypot(p0[2]), p1[0].hypot(p1[2]));
+                let a0 = p0[2].atan2(p0[0]);
+                let turn = (p1[2].atan2(p1[0]) - a0 + PI).rem_euclid(TAU) - PI;
+
+                let (angle, radius) = (a0 + t * turn, r0 + t * (r1 - r0));
+                vec![
+                    radius * angle.cos(),
+                    p0[1] + t * (p1[1] - p0[1]),
+                    radius * angle.sin(),
+                ]
+            }
+        }
+    }
+}
+
+fn parse_path(property: &str) -> Result<Vec<PathSegment>> {
+    property
+        .split('.')
+        .map(|segment| match segment.parse() {
+            Ok(index) => Ok(PathSegment::Index(index)),
+            Err(_) if !segment.is_empty() => Ok(PathSegment::Key(segment.to_owned())),
+            Err(_) => bail!("`{property}` isn't a property path"),
+        })
+        .collect()
+}
+
+/// Add up lists of numbers, each scaled by its weight.
+fn combine(terms: &[(&Vec<f64>, f64)]) -> Vec<f64> {
+    let mut sum = vec![0.; terms[0].0.len()];
+
+    for (values, weight) in terms {
+        for (total, value) in sum.iter_mut().zip(values.iter()) {
+            *total += weight * value;
+        }
+    }
+
+    sum
+}
@@ -0,0 +1,145 @@
+use std::f64::consts::TAU;
+
+use na::{Point3, Vector3};
+use rand::Rng;
+
+use crate::geometry::plane::Plane;
+use crate::geometry::ray::Hittable;
+use crate::geometry::sphere::Sphere;
+use crate::materials::{Dielectric, Lambertian, Metal};
+use crate::scene::animation::{Interpolation, Track};
+use crate::scene::{CameraSettings, Scene};
+use crate::tonemap::ToneMapper;
+use crate::util::{color, random_color};
+
+/// The final scene from _Ray Tracing in One Weekend_: three large spheres surrounded by a
+/// randomly generated field of small ones, with the camera orbiting the center.
+pub fn sphere_field(rng: &mut impl Rng) -> Scene {
+    let mut world: Vec<Box<dyn Hittable>> = Vec::new();
+
+    let material_ground = Lambertian::new(color(0.5, 0.5, 0.5));
+    world.push(Box::new(Plane::new(
+        Point3::origin(),
+        Vector3::y(),
+        material_ground,
+    )));
+
+    for a in -11..11 {
+        for b in -11..11 {
+            let a = f64::from(a);
+            let b = f64::from(b);
+
+            let choose_mat = rng.gen::<f64>();
+            let center = Point3::new(a + 0.9 * rng.gen::<f64>(), 0.2, b + 0.9 * rng.gen::<f64>());
+
+            if (center - Point3::new(4., 0.2, 0.)).magnitude() > 0.9 {
+                if choose_mat < 0.8 {
+                    let albedo = random_color(rng);
+                    let material = Lambertian::new(albedo);
+
+                    world.push(Box::new(Sphere::new(center, 0.2, material)));
+                } else if choose_mat < 0.95 {
+                    let albedo = color(
+                        0.5 * rng.gen::<f64>() + 0.5,
+                        0.5 * rng.gen::<f64>() + 0.5,
+                        0.5 * rng.gen::<f64>() + 0.5,
+                    );
+                    let fuzz = 0.5 * rng.gen::<f64>() + 0.5;
+                    let material = Metal::new(albedo, fuzz);
+
+                    world.push(Box::new(Sphere::new(center, 0.2, material)));
+                } else {
+                    // Glass
+                    let material = Dielectric::new(1.5);
+
+                    world.push(Box::new(Sphere::new(center, 0.2, material)));
+                }
+            }
+        }
+    }
+
+    let material_1 = Dielectric::new(1.5);
+    world.push(Box::new(Sphere::new(
+        Point3::new(0., 1., 0.),
+        1.,
+        material_1,
+    )));
+
+    let material_2 = Lambertian::new(color(0.4, 0.2, 0.1));
+    world.push(Box::new(Sphere::new(
+        Point3::new(-4., 1., 0.),
+        1.,
+        material_2,
+    )));
+
+    let material_3 = Metal::new(color(0.7, 0.6, 0.5), 0.0);
+    world.push(Box::new(Sphere::new(
+        Point3::new(4., 1., 0.),
+        1.,
+        material_3,
+    )));
+
+    let camera = CameraSettings {
+        image_size: (1200, 675),
+        vertical_fov: Some(20.),
+        look_from: None,
+        look_at: Some(Point3::new(0., 0., 0.)),
+        up_vector: None,
+        focus_dist: Some(10.),
+        defocus_angle: Some(0.6),
+        samples_per_pixel: Some(10),
+        max_depth: None,
+        seed: None,
+        sampler: None,
+        filter: None,
+        filter_radius: None,
+        tile_size: None,
+        tile_order: None,
+        time_limit: None,
+        noise_threshold: None,
+        min_samples_per_pixel: None,
+        shutter_open: None,
+        shutter_close: None,
+        environment: None,
+    };
+
+    Scene {
+        camera,
+        world,
+        tone_mapper: ToneMapper::default(),
+        frames: ORBIT_FRAMES,
+        warnings: Vec::new(),
+        tracks: vec![orbit()],
+        source: None,
+    }
+}
+
+/// The number of frames the camera takes to circle the scene once.
+const ORBIT_FRAMES: u32 = 30 * 10;
+
+/// A camera path circling horizontally around the center of the scene, starting at the
+/// book's camera position of (13, 2, 3).
+///
+/// The keyframes are a quarter turn apart, and orbiting between them keeps the camera on
+/// the circle exactly.
+fn orbit() -> Track {
+    const KEYFRAMES: i32 = 4;
+
+    let center = Point3::new(0., 2., 0.);
+    let radius = 178f64.sqrt();
+    let phase_offset = (3. / radius).asin();
+    let spacing = f64::from(ORBIT_FRAMES) / f64::from(KEYFRAMES);
+
+    let keyframes = (0..=KEYFRAMES).map(|i| {
+        let t = TAU * f64::from(i) / f64::from(KEYFRAMES) + phase_offset;
+        let position = center + radius * Vector3::new(t.cos(), 0., t.sin());
+
+        (
+            1. + f64::from(i) * spacing,
+            position.coords.as_slice().to_vec(),
+        )
+    });
+
+    Track::through("camera.look_from", Interpolation::Orbit, keyframes)
+        .expect("the orbit's keyframes are valid")
+}
@@ -0,0 +1,496 @@
+//! The on-disk format of scene files.
+//!
+//! These types mirror the structure of the TOML document directly, and are turned into
+//! renderable objects by [`super::loader`].
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use na::{Point2, Point3, Vector3};
+use serde::de::IgnoredAny;
+use serde::{Deserialize, Serialize};
+use toml::Spanned;
+
+use crate::camera::Camera;
+use crate::environments::Environment;
+use crate::filter::FilterKind;
+use crate::samplers::SamplerKind;
+use crate::scene::animation::Interpolation;
+use crate::tiles::TileOrder;
+use crate::tonemap::ToneMapper;
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct SceneDescription {
+    pub camera: CameraSettings,
+    pub environment: Option<EnvironmentDescription>,
+    #[serde(default)]
+    pub output: ToneMapper,
+    #[serde(default)]
+    pub textures: HashMap<String, TextureDescription>,
+    #[serde(default)]
+    pub materials: HashMap<String, MaterialDescription>,
+    #[serde(default)]
+    pub objects: Vec<ObjectDescription>,
+    /// Groups of objects that can be placed any number of times by `instance` objects.
+    #[serde(default)]
+    pub prototypes: HashMap<String, Vec<ObjectDescription>>,
+    pub animation: Option<AnimationDescription>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct AnimationDescription {
+    /// The number of frames, which defaults to running until the last keyframe.
+    pub frames: Option<u32>,
+    #[serde(default)]
+    pub tracks: Vec<TrackDescription>,
+}
+
+/// Where the parts of a scene file that errors point to are in the file.
+///
+/// These are kept apart from the [`SceneDescription`] because the descriptions of animated
+/// frames are read from the document after the tracks have changed it, rather than from the
+/// file, so they don't have any. Tracks only ever change values inside these parts, so the
+/// file's spans are right for every frame.
+#[derive(Deserialize, Debug)]
+pub struct SceneSpans {
+    #[serde(default)]
+    pub textures: HashMap<String, Spanned<IgnoredAny>>,
+    #[serde(default)]
+    pub materials: HashMap<String, Spanned<IgnoredAny>>,
+    #[serde(default)]
+    pub objects: Vec<Spanned<IgnoredAny>>,
+    #[serde(default)]
+    pub prototypes: HashMap<String, Vec<Spanned<IgnoredAny>>>,
+    pub animation: Option<AnimationSpans>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct AnimationSpans {
+    #[serde(default)]
+    pub tracks: Vec<Spanned<IgnoredAny>>,
+}
+
+/// A property of the scene that changes from frame to frame.
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct TrackDescription {
+    /// The path to the property through the scene file, like `camera.look_from` or
+    /// `objects.0.radius`, counting array elements from zero.
+    pub property: String,
+    #[serde(default)]
+    pub interpolation: Interpolation,
+    pub keyframes: Vec<TrackKeyframeDescription>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct TrackKeyframeDescription {
+    /// The frame the property has this value at, numbered from 1 like the output frames.
+    pub frame: f64,
+    pub value: TrackValue,
+    /// The Bézier handles the curve is pulled towards arriving at and leaving this keyframe.
+    #[serde(rename = "in")]
+    pub in_handle: Option<TrackValue>,
+    #[serde(rename = "out")]
+    pub out_handle: Option<TrackValue>,
+}
+
+/// A value that can be animated: a number, or a list of them like a point or a color.
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+pub enum TrackValue {
+    Number(Number),
+    List(Vec<Number>),
+}
+
+#[derive(Copy, Clone, Deserialize, Debug)]
+#[serde(untagged)]
+pub enum Number {
+    Integer(i64),
+    Float(f64),
+}
+
+impl Number {
+    #[allow(clippy::cast_precision_loss)]
+    fn to_f64(self) -> f64 {
+        match self {
+            Number::Integer(x) => x as f64,
+            Number::Float(x) => x,
+        }
+    }
+}
+
+impl TrackValue {
+    pub fn to_vec(&self) -> Vec<f64> {
+        match self {
+            TrackValue::Number(x) => vec![x.to_f64()],
+            TrackValue::List(xs) => xs.iter().map(|x| x.to_f64()).collect(),
+        }
+    }
+
+    /// The number of elements in the value if it's a list, or `None` if it's a single number.
+    pub fn shape(&self) -> Option<usize> {
+        match self {
+            TrackValue::Number(_) => None,
+            TrackValue::List(xs) => Some(xs.len()),
+        }
+    }
+
+    /// Whether the value is made up only of integers.
+    pub fn is_integer(&self) -> bool {
+        match self {
+            TrackValue::Number(x) => matches!(x, Number::Integer(_)),
+            TrackValue::List(xs) => xs.iter().all(|x| matches!(x, Number::Integer(_))),
+        }
+    }
+}
+
+/// Everything needed to build a [`Camera`], with the same defaults as
+/// [`Camera::builder`] for anything left unspecified.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct CameraSettings {
+    pub image_size: (u32, u32),
+    pub vertical_fov: Option<f64>,
+    pub look_from: Option<Point3<f64>>,
+    pub look_at: Option<Point3<f64>>,
+    pub up_vector: Option<Vector3<f64>>,
+    pub focus_dist: Option<f64>,
+    pub defocus_angle: Option<f64>,
+    pub samples_per_pixel: Option<usize>,
+    pub max_depth: Option<usize>,
+    /// The seed for the random numbers used while rendering.
+    pub seed: Option<u64>,
+    pub sampler: Option<SamplerKind>,
+    pub filter: Option<FilterKind>,
+    /// The reconstruction filter's radius, in pixels.
+    pub filter_radius: Option<f64>,
+    pub tile_size: Option<u32>,
+    pub tile_order: Option<TileOrder>,
+    /// The longest a render may take, in seconds.
+    pub time_limit: Option<f64>,
+    /// The relative error every pixel must be under for rendering to stop early.
+    pub noise_threshold: Option<f64>,
+    /// Turns on adaptive sampling, with every pixel getting at least this many samples.
+    pub min_samples_per_pixel: Option<usize>,
+    /// The times the shutter opens and closes, for motion blur.
+    pub shutter_open: Option<f64>,
+    pub shutter_close: Option<f64>,
+    /// Filled in from the scene file's separate `[environment]` table.
+    #[serde(skip)]
+    pub environment: Option<Arc<dyn Environment>>,
+}
+
+impl CameraSettings {
+    pub fn build(&self) -> Camera {
+        Camera::builder()
+            .image_size(self.image_size)
+            .and_vertical_fov(self.vertical_fov)
+            .and_look_from(self.look_from)
+            .and_look_at(self.look_at)
+            .and_up_vector(self.up_vector)
+            .and_focus_dist(self.focus_dist)
+            .and_defocus_angle(self.defocus_angle)
+            .and_samples_per_pixel(self.samples_per_pixel)
+            .and_max_depth(self.max_depth)
+            .and_environment(self.environment.clone())
+            .and_seed(self.seed)
+            .and_sampler(self.sampler)
+            .and_filter(self.filter)
+            .and_filter_radius(self.filter_radius)
+            .and_tile_size(self.tile_size)
+            .and_tile_order(self.tile_order)
+            .and_time_limit(self.time_limit.map(Duration::from_secs_f64))
+            .and_noise_threshold(self.noise_threshold)
+            .and_min_samples_per_pixel(self.min_samples_per_pixel)
+            .and_shutter_open(self.shutter_open)
+            .and_shutter_close(self.shutter_close)
+            .build()
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum EnvironmentDescription {
+    SolidColor {
+        color: Vector3<f64>,
+    },
+    Gradient {
+        bottom: Vector3<f64>,
+        top: Vector3<f64>,
+    },
+    /// An equirectangular environment map loaded from an image file.
+    Map {
+        path: PathBuf,
+        /// The rotation around the vertical axis, in degrees.
+        #[serde(default)]
+        rotation: f64,
+        #[serde(default = "default_intensity")]
+        intensity: f64,
+    },
+}
+
+/// Either a constant color, or the name of a texture.
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+pub enum ColorOrTexture {
+    Color(Vector3<f64>),
+    Texture(String),
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum TextureDescription {
+    SolidColor {
+        color: Vector3<f64>,
+    },
+    /// A 3D checkerboard of cubes `scale` units across.
+    Checker {
+        #[serde(default = "default_scale")]
+        scale: f64,
+        even: ColorOrTexture,
+        odd: ColorOrTexture,
+    },
+    Image {
+        path: PathBuf,
+    },
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum MaterialDescription {
+    Lambertian {
+        albedo: ColorOrTexture,
+    },
+    Metal {
+        albedo: ColorOrTexture,
+        #[serde(default)]
+        fuzz: f64,
+    },
+    Dielectric {
+        refractive_index: f64,
+        tint: Option<Vector3<f64>>,
+    },
+    DiffuseLight {
+        color: Vector3<f64>,
+        #[serde(default = "default_intensity")]
+        intensity: f64,
+    },
+}
+
+fn default_intensity() -> f64 {
+    1.
+}
+
+fn default_scale() -> f64 {
+    1.
+}
+
+fn default_capped() -> bool {
+    true
+}
+
+fn default_power() -> f64 {
+    8.
+}
+
+fn default_iterations() -> u32 {
+    10
+}
+
+/// An object, and where it's placed.
+///
+/// Unknown fields are caught by the shape, which gets every field but these.
+#[derive(Deserialize, Debug)]
+pub struct ObjectDescription {
+    #[serde(flatten)]
+    pub shape: ShapeDescription,
+    pub transform: Option<TransformDescription>,
+    pub motion: Option<MotionDescription>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum ShapeDescription {
+    /// A sphere, which is cut off below `y_min` and above `y_max` relative to its center,
+    /// and sweeps `phi_max` degrees around the y axis, if any of them are given.
+    Sphere {
+        center: Point3<f64>,
+        radius: f64,
+        y_min: Option<f64>,
+        y_max: Option<f64>,
+        phi_max: Option<f64>,
+        material: String,
+    },
+    /// An upright cylinder with the center of its bottom at `base`.
+    Cylinder {
+        base: Point3<f64>,
+        radius: f64,
+        height: f64,
+        #[serde(default = "default_capped")]
+        capped: bool,
+        material: String,
+    },
+    /// An upright cone with the center of its bottom at `base`, and its point `height`
+    /// above it.
+    Cone {
+        base: Point3<f64>,
+        radius: f64,
+        height: f64,
+        #[serde(default = "default_capped")]
+        capped: bool,
+        material: String,
+    },
+    /// A torus lying flat around `center`.
+    Torus {
+        center: Point3<f64>,
+        major_radius: f64,
+        minor_radius: f64,
+        material: String,
+    },
+    Triangle {
+        vertices: [Point3<f64>; 3],
+        normals: Option<[Vector3<f64>; 3]>,
+        uvs: Option<[Point2<f64>; 3]>,
+        material: String,
+    },
+    /// A parallelogram with a corner at `corner` and sides along `u` and `v`.
+    Quad {
+        corner: Point3<f64>,
+        u: Vector3<f64>,
+        v: Vector3<f64>,
+        material: String,
+    },
+    Disk {
+        center: Point3<f64>,
+        normal: Vector3<f64>,
+        radius: f64,
+        material: String,
+    },
+    /// An infinite plane through `point`.
+    Plane {
+        point: Point3<f64>,
+        normal: Vector3<f64>,
+        material: String,
+    },
+    /// An axis-aligned box with opposite corners at `corners`.
+    Box {
+        corners: [Point3<f64>; 2],
+        material: String,
+    },
+    /// A triangle mesh loaded from a Wavefront OBJ file.
+    Mesh {
+        path: PathBuf,
+        /// The material for faces that don't have one in the OBJ file.
+        material: Option<String>,
+        /// Replacements for the OBJ file's materials, by name.
+        #[serde(default)]
+        materials: HashMap<String, String>,
+    },
+    /// Another copy of the objects in one of the scene's prototypes, sharing their memory
+    /// with every other instance of it.
+    Instance { prototype: String },
+    /// Everything inside any of `objects`, which have to be closed solids.
+    Union { objects: Vec<ObjectDescription> },
+    /// Everything inside all of `objects`, which have to be closed solids.
+    Intersection { objects: Vec<ObjectDescription> },
+    /// The first of `objects`, with the rest cut out of it. They all have to be closed solids.
+    Difference { objects: Vec<ObjectDescription> },
+    /// A shape given by a signed distance function, drawn by sphere tracing.
+    Sdf {
+        sdf: SdfDescription,
+        material: String,
+        /// How close to the surface a ray has to get to hit it.
+        epsilon: Option<f64>,
+        /// The most steps a ray takes before it's taken to have missed.
+        max_steps: Option<u32>,
+    },
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum SdfDescription {
+    Sphere {
+        #[serde(default = "Point3::origin")]
+        center: Point3<f64>,
+        radius: f64,
+    },
+    Box {
+        #[serde(default = "Point3::origin")]
+        center: Point3<f64>,
+        size: Vector3<f64>,
+    },
+    Torus {
+        #[serde(default = "Point3::origin")]
+        center: Point3<f64>,
+        major_radius: f64,
+        minor_radius: f64,
+    },
+    /// All of `shapes`, blended together where they're within `smoothness` of each other.
+    SmoothUnion {
+        shapes: Vec<SdfDescription>,
+        smoothness: f64,
+    },
+    /// A grid of copies of `shape`, `spacing` apart, with `limit` more on each side of it
+    /// along each axis.
+    Repeat {
+        shape: Box<SdfDescription>,
+        spacing: Vector3<f64>,
+        limit: [u32; 3],
+    },
+    /// `shape` twisted around the y axis, by `rate` degrees for every unit up it.
+    Twist {
+        shape: Box<SdfDescription>,
+        rate: f64,
+    },
+    /// `shape` with its edges rounded off to `radius`.
+    Round {
+        shape: Box<SdfDescription>,
+        radius: f64,
+    },
+    Mandelbulb {
+        #[serde(default = "default_power")]
+        power: f64,
+        #[serde(default = "default_iterations")]
+        iterations: u32,
+    },
+}
+
+/// An affine transform, applied as a scale, then a rotation, then a translation.
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct TransformDescription {
+    pub scale: Option<Scale>,
+    /// Rotations around the x, y and z axes in degrees, applied in that order.
+    pub rotate: Option<Vector3<f64>>,
+    pub translate: Option<Vector3<f64>>,
+}
+
+/// Either the same scale factor along every axis, or one for each.
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+pub enum Scale {
+    Uniform(f64),
+    PerAxis(Vector3<f64>),
+}
+
+/// How an object moves over time, as offsets from where it's placed.
+#[derive(Deserialize, Debug)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum MotionDescription {
+    /// Moving at a constant speed, by `offset` between time 0 and time 1.
+    Linear { offset: Vector3<f64> },
+    /// Passing through each keyframe's offset at its time, in straight lines between them.
+    Keyframed { keyframes: Vec<KeyframeDescription> },
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct KeyframeDescription {
+    pub time: f64,
+    pub offset: Vector3<f64>,
+}
@@ -0,0 +1,742 @@
+//! Turning a [`SceneDescription`] into the objects it describes.
+
+use std::collections::HashMap;
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use anyhow::{anyhow, bail, Context, Result};
+use image::Rgb32FImage;
+use na::{Affine3, Matrix4, Rotation3, Unit, Vector3};
+use serde::de::IgnoredAny;
+use toml::Spanned;
+
+use crate::environments::{self, Environment, EnvironmentMap, Gradient};
+use crate::geometry::bvh::Bvh;
+use crate::geometry::clipped_sphere::ClippedSphere;
+use crate::geometry::cone::Cone;
+use crate::geometry::csg::{Csg, Operation};
+use crate::geometry::cuboid::Cuboid;
+use crate::geometry::cylinder::Cylinder;
+use crate::geometry::disk::Disk;
+use crate::geometry::moving::{Motion, Moving};
+use crate::geometry::obj::ObjFile;
+use crate::geometry::plane::Plane;
+use crate::geometry::quad::Quad;
+use crate::geometry::ray::Hittable;
+use crate::geometry::sdf::combinators::{Repeat, Round, SmoothUnion, Twist};
+use crate::geometry::sdf::mandelbulb::Mandelbulb;
+use crate::geometry::sdf::primitives::{SdfBox, SdfSphere, SdfTorus};
+use crate::geometry::sdf::{self, Sdf, SphereTraced};
+use crate::geometry::sphere::Sphere;
+use crate::geometry::torus::Torus;
+use crate::geometry::transformed::Transformed;
+use crate::geometry::triangle::Triangle;
+use crate::materials::{Dielectric, DiffuseLight, Lambertian, Material, Metal};
+use crate::scene::description::{
+    ColorOrTexture, EnvironmentDescription, MaterialDescription, MotionDescription,
+    ObjectDescription, Scale, SceneDescription, SceneSpans, SdfDescription, ShapeDescription,
+    TextureDescription, TransformDescription,
+};
+use crate::textures::{self, Checker, ImageTexture, Texture};
+use crate::util::color;
+
+/// The files that scenes refer to, kept once they've been loaded so that animations that
+/// rebuild the world for every frame don't read them all over again.
+#[derive(Default)]
+pub struct AssetCache {
+    images: Mutex<HashMap<PathBuf, Arc<ImageTexture>>>,
+    environment_maps: Mutex<HashMap<PathBuf, Arc<Rgb32FImage>>>,
+    meshes: Mutex<HashMap<PathBuf, Arc<ObjFile>>>,
+    /// Problems with the files that they could still be used in spite of.
+    warnings: Mutex<Vec<String>>,
+}
+
+impl AssetCache {
+    fn image(&self, path: &Path) -> Result<Arc<ImageTexture>> {
+        Self::get_or_load(&self.images, path, ImageTexture::load)
+    }
+
+    fn environment_map(&self, path: &Path) -> Result<Arc<Rgb32FImage>> {
+        Self::get_or_load(&self.environment_maps, path, EnvironmentMap::load_image)
+    }
+
+    fn mesh(&self, path: &Path) -> Result<Arc<ObjFile>> {
+        Self::get_or_load(&self.meshes, path, |path| {
+            let obj = ObjFile::read(path)?;
+            if let Some(error) = &obj.mtl_error {
+                self.warnings
+                    .lock()
+                    .expect("the asset cache's lock was poisoned")
+                    .push(format!(
+                        "couldn't load the materials for {}, so it'll use its default material: \
+                         {error}",
+                        path.display()
+                    ));
+            }
+
+            Ok(obj)
+        })
+    }
+
+    /// Take the warnings about the files loaded since the last time this was called.
+    pub fn take_warnings(&self) -> Vec<String> {
+        mem::take(
+            &mut *self
+                .warnings
+                .lock()
+                .expect("the asset cache's lock was poisoned"),
+        )
+    }
+
+    fn get_or_load<T>(
+        cache: &Mutex<HashMap<PathBuf, Arc<T>>>,
+        path: &Path,
+        load: impl FnOnce(&Path) -> Result<T>,
+    ) -> Result<Arc<T>> {
+        let mut cache = cache.lock().expect("the asset cache's lock was poisoned");
+        if let Some(asset) = cache.get(path) {
+            return Ok(Arc::clone(asset));
+        }
+
+        let asset = Arc::new(load(path)?);
+        cache.insert(path.to_owned(), Arc::clone(&asset));
+        Ok(asset)
+    }
+}
+
+pub struct Loader<'a> {
+    /// The scene file, which `spans` point into.
+    source: &'a str,
+    base_dir: &'a Path,
+    description: &'a SceneDescription,
+    spans: &'a SceneSpans,
+    assets: &'a AssetCache,
+    textures: HashMap<&'a str, Arc<dyn Texture>>,
+    /// The textures currently being built, to catch any that end up referring to themselves.
+    textures_in_progress: Vec<&'a str>,
+    materials: HashMap<&'a str, Box<dyn Material>>,
+    /// The prototypes built so far, which are shared between all of their instances.
+    prototypes: HashMap<&'a str, Arc<dyn Hittable>>,
+    /// The prototypes currently being built, to catch any that end up containing themselves.
+    prototypes_in_progress: Vec<&'a str>,
+}
+
+impl<'a> Loader<'a> {
+    pub fn new(
+        source: &'a str,
+        base_dir: &'a Path,
+        description: &'a SceneDescription,
+        spans: &'a SceneSpans,
+        assets: &'a AssetCache,
+    ) -> Self {
+        Self {
+            source,
+            base_dir,
+            description,
+            spans,
+            assets,
+            textures: HashMap::new(),
+            textures_in_progress: Vec::new(),
+            materials: HashMap::new(),
+            prototypes: HashMap::new(),
+            prototypes_in_progress: Vec::new(),
+        }
+    }
+
+    /// Get the one-based line number that a spanned value starts on.
+    pub fn line_of<T>(&self, spanned: &Spanned<T>) -> usize {
+        let offset = spanned.span().start.min(self.source.len());
+        self.source[..offset].matches('\n').count() + 1
+    }
+
+    pub fn environment(&self) -> Result<Option<Arc<dyn Environment>>> {
+        let Some(environment) = &self.description.environment else {
+            return Ok(None);
+        };
+
+        Ok(Some(match environment {
+            EnvironmentDescription::SolidColor { color } => {
+                Arc::new(environments::SolidColor::new(*color))
+            }
+            EnvironmentDescription::Gradient { bottom, top } => {
+                Arc::new(Gradient::new(*bottom, *top))
+            }
+            EnvironmentDescription::Map {
+                path,
+                rotation,
+                intensity,
+            } => Arc::new(EnvironmentMap::new(
+                self.assets.environment_map(&self.base_dir.join(path))?,
+                *rotation,
+                *intensity,
+            )),
+        }))
+    }
+
+    pub fn world(&mut self) -> Result<Vec<Box<dyn Hittable>>> {
+        self.objects(&self.description.objects, &self.spans.objects)
+    }
+
+    /// Build `objects`, which are where `spans` say they are in the scene file.
+    fn objects(
+        &mut self,
+        objects: &'a [ObjectDescription],
+        spans: &[Spanned<IgnoredAny>],
+    ) -> Result<Vec<Box<dyn Hittable>>> {
+        let mut built: Vec<Box<dyn Hittable>> = Vec::with_capacity(objects.len());
+
+        for (index, object) in objects.iter().enumerate() {
+            let line = self.line_of(&spans[index]);
+            let object = self
+                .placed_object(object)
+                .with_context(|| format!("in the object at line {line}"))?;
+
+            built.push(object);
+        }
+
+        Ok(built)
+    }
+
+    /// Build an object, placed with its transform and set moving along its motion if it has
+    /// either.
+    fn placed_object(&mut self, object: &'a ObjectDescription) -> Result<Box<dyn Hittable>> {
+        let transform = object.transform.as_ref().map(Self::transform).transpose()?;
+        let motion = object.motion.as_ref().map(Self::motion).transpose()?;
+        let mut placed = self.shape(&object.shape)?;
+
+        if let Some(transform) = transform {
+            placed = Box::new(Transformed::new(placed, transform));
+        }
+        if let Some(motion) = motion {
+            placed = Box::new(Moving::new(placed, motion));
+        }
+
+        Ok(placed)
+    }
+
+    fn transform(description: &TransformDescription) -> Result<Affine3<f64>> {
+        let scale = match description.scale {
+            Some(Scale::Uniform(scale)) => Vector3::repeat(scale),
+            Some(Scale::PerAxis(scale)) => scale,
+            None => Vector3::repeat(1.),
+        };
+        if let Some(scale) = scale.iter().find(|&&s| s == 0. || !s.is_finite()) {
+            bail!("objects can't be scaled by {scale}");
+        }
+
+        let angles = description
+            .rotate
+            .unwrap_or_else(Vector3::zeros)
+            .map(f64::to_radians);
+        let rotation = Rotation3::from_euler_angles(angles.x, angles.y, angles.z);
+        let translation = description.translate.unwrap_or_else(Vector3::zeros);
+
+        Ok(Affine3::from_matrix_unchecked(
+            Matrix4::new_translation(&translation)
+                * rotation.to_homogeneous()
+                * Matrix4::new_nonuniform_scaling(&scale),
+        ))
+    }
+
+    fn motion(description: &MotionDescription) -> Result<Motion> {
+        Ok(match description {
+            MotionDescription::Linear { offset } => Motion::linear(*offset),
+            MotionDescription::Keyframed { keyframes } => {
+                if keyframes.is_empty() {
+                    bail!("keyframed motion needs at least one keyframe");
+                }
+                if let Some(keyframe) = keyframes.iter().find(|k| !k.time.is_finite()) {
+                    bail!("keyframe has a time of {}", keyframe.time);
+                }
+
+                Motion::new(keyframes.iter().map(|k| (k.time, k.offset)).collect())
+            }
+        })
+    }
+
+    fn shape(&mut self, shape: &'a ShapeDescription) -> Result<Box<dyn Hittable>> {
+        Ok(match shape {
+            ShapeDescription::Sphere {
+                center,
+                radius,
+                y_min,
+                y_max,
+                phi_max,
+                material,
+            } => {
+                if !(*radius > 0. && radius.is_finite()) {
+                    bail!("sphere's radius has to be positive and finite, not {radius}");
+                }
+
+                if y_min.is_none() && y_max.is_none() && phi_max.is_none() {
+                    Box::new(Sphere {
+                        center: *center,
+                        radius: *radius,
+                        material: self.material(material)?,
+                    })
+                } else {
+                    let y_min = y_min.unwrap_or(-radius).max(-radius);
+                    let y_max = y_max.unwrap_or(*radius).min(*radius);
+                    let phi_max = phi_max.unwrap_or(360.);
+                    if y_min >= y_max {
+                        bail!("sphere is cut off everywhere between y = {y_min} and {y_max}");
+                    }
+                    if !(phi_max > 0. && phi_max <= 360.) {
+                        bail!("sphere's `phi_max` of {phi_max} isn't between 0 and 360 degrees");
+                    }
+
+                    Box::new(ClippedSphere {
+                        center: *center,
+                        radius: *radius,
+                        y_min,
+                        y_max,
+                        phi_max: phi_max.to_radians(),
+                        material: self.material(material)?,
+                    })
+                }
+            }
+            ShapeDescription::Cylinder {
+                base,
+                radius,
+                height,
+                capped,
+                material,
+            } => {
+                Self::check_size("cylinder", *radius, *height)?;
+
+                Box::new(Cylinder {
+                    base: *base,
+                    radius: *radius,
+                    height: *height,
+                    capped: *capped,
+                    material: self.material(material)?,
+                })
+            }
+            ShapeDescription::Cone {
+                base,
+                radius,
+                height,
+                capped,
+                material,
+            } => {
+                Self::check_size("cone", *radius, *height)?;
+
+                Box::new(Cone {
+                    base: *base,
+                    radius: *radius,
+                    height: *height,
+                    capped: *capped,
+                    material: self.material(material)?,
+                })
+            }
+            ShapeDescription::Torus {
+                center,
+                major_radius,
+                minor_radius,
+                material,
+            } => {
+                if ![major_radius, minor_radius]
+                    .iter()
+                    .all(|&&radius| radius > 0. && radius.is_finite())
+                {
+                    bail!("torus's radii have to be positive and finite");
+                }
+
+                Box::new(Torus {
+                    center: *center,
+                    major_radius: *major_radius,
+                    minor_radius: *minor_radius,
+                    material: self.material(material)?,
+                })
+            }
+            ShapeDescription::Triangle {
+                vertices,
+                normals,
+                uvs,
+                material,
+            } => Box::new(Triangle {
+                vertices: *vertices,
+                normals: normals.map(|normals| normals.map(Unit::new_normalize)),
+                uvs: *uvs,
+                material: self.material(material)?,
+            }),
+            ShapeDescription::Quad {
+                corner,
+                u,
+                v,
+                material,
+            } => {
+                if u.cross(v).magnitude_squared() == 0. {
+                    bail!("quad has parallel or zero-length sides");
+                }
+
+                Box::new(Quad {
+                    corner: *corner,
+                    u: *u,
+                    v: *v,
+                    material: self.material(material)?,
+                })
+            }
+            ShapeDescription::Disk {
+                center,
+                normal,
+                radius,
+                material,
+            } => {
+                if !(*radius > 0. && radius.is_finite()) {
+                    bail!("disk's radius has to be positive and finite, not {radius}");
+                }
+
+                Box::new(Disk {
+                    center: *center,
+                    normal: Self::normal(normal, "disk")?,
+                    radius: *radius,
+                    material: self.material(material)?,
+                })
+            }
+            ShapeDescription::Plane {
+                point,
+                normal,
+                material,
+            } => Box::new(Plane {
+                point: *point,
+                normal: Self::normal(normal, "plane")?,
+                material: self.material(material)?,
+            }),
+            ShapeDescription::Box { corners, material } => {
+                let [a, b] = corners;
+                if (0..3).any(|axis| a[axis] == b[axis]) {
+                    bail!("box from {a} to {b} is flat");
+                }
+
+                Box::new(Cuboid::new(a, b, self.material(material)?))
+            }
+            ShapeDescription::Mesh {
+                path,
+                material,
+                materials,
+            } => {
+                let default_material = match material {
+                    Some(material) => self.material(material)?,
+                    None => Box::new(Lambertian::new(color(0.8, 0.8, 0.8))),
+                };
+                let overrides = materials
+                    .iter()
+                    .map(|(group, material)| Ok((group.clone(), self.material(material)?)))
+                    .collect::<Result<HashMap<_, _>>>()?;
+
+                let obj = self.assets.mesh(&self.base_dir.join(path))?;
+                Box::new(obj.mesh(&overrides, default_material, |path| self.assets.image(path))?)
+            }
+            ShapeDescription::Instance { prototype } => Box::new(self.prototype(prototype)?),
+            ShapeDescription::Union { objects } => self.csg(Operation::Union, objects)?,
+            ShapeDescription::Intersection { objects } => {
+                self.csg(Operation::Intersection, objects)?
+            }
+            ShapeDescription::Difference { objects } => self.csg(Operation::Difference, objects)?,
+            ShapeDescription::Sdf {
+                sdf,
+                material,
+                epsilon,
+                max_steps,
+            } => {
+                let epsilon = epsilon.unwrap_or(sdf::DEFAULT_EPSILON);
+                if !(epsilon > 0. && epsilon.is_finite()) {
+                    bail!(
+                        "signed distance function's epsilon has to be positive and finite, not \
+                         {epsilon}"
+                    );
+                }
+
+                Box::new(SphereTraced {
+                    sdf: Self::sdf(sdf)?,
+                    material: self.material(material)?,
+                    epsilon,
+                    max_steps: max_steps.unwrap_or(sdf::DEFAULT_MAX_STEPS),
+                })
+            }
+        })
+    }
+
+    /// Combine `objects` into one solid, one after another from the first.
+    fn csg(
+        &mut self,
+        operation: Operation,
+        objects: &'a [ObjectDescription],
+    ) -> Result<Box<dyn Hittable>> {
+        if objects.len() < 2 {
+            bail!(
+                "combining solids needs at least two objects, but there are {}",
+                objects.len()
+            );
+        }
+
+        let mut operands = Vec::with_capacity(objects.len());
+        for (index, object) in objects.iter().enumerate() {
+            let operand = self
+                .placed_object(object)
+                .with_context(|| format!("in solid {} of the combination", index + 1))?;
+            operands.push(operand);
+        }
+
+        let mut operands = operands.into_iter();
+        let first = operands.next().unwrap();
+        Ok(operands.fold(first, |combined, operand| {
+            Box::new(Csg::new(operation, combined, operand))
+        }))
+    }
+
+    fn sdf(description: &SdfDescription) -> Result<Box<dyn Sdf>> {
+        Ok(match description {
+            SdfDescription::Sphere { center, radius } => {
+                if !(*radius > 0. && radius.is_finite()) {
+                    bail!("sphere's radius has to be positive and finite, not {radius}");
+                }
+
+                Box::new(SdfSphere {
+                    center: *center,
+                    radius: *radius,
+                })
+            }
+            SdfDescription::Box { center, size } => {
+                if !size.iter().all(|&s| s > 0. && s.is_finite()) {
+                    bail!("box's size has to be positive and finite, not {size}");
+                }
+
+                Box::new(SdfBox {
+                    center: *center,
+                    half_size: size / 2.,
+                })
+            }
+            SdfDescription::Torus {
+                center,
+                major_radius,
+                minor_radius,
+            } => {
+                if ![major_radius, minor_radius]
+                    .iter()
+                    .all(|&&radius| radius > 0. && radius.is_finite())
+                {
+                    bail!("torus's radii have to be positive and finite");
+                }
+
+                Box::new(SdfTorus {
+                    center: *center,
+                    major_radius: *major_radius,
+                    minor_radius: *minor_radius,
+                })
+            }
+            SdfDescription::SmoothUnion { shapes, smoothness } => {
+                if !(*smoothness >= 0. && smoothness.is_finite()) {
+                    bail!(
+                        "smooth union's smoothness has to be zero or more and finite, not \
+                         {smoothness}"
+                    );
+                }
+
+                let mut shapes = shapes.iter().map(Self::sdf);
+                let first = shapes
+                    .next()
+                    .context("smooth union doesn't have any shapes")??;
+                shapes.try_fold(first, |a, b| -> Result<Box<dyn Sdf>> {
+                    Ok(Box::new(SmoothUnion {
+                        a,
+                        b: b?,
+                        smoothness: *smoothness,
+                    }))
+                })?
+            }
+            SdfDescription::Repeat {
+                shape,
+                spacing,
+                limit,
+            } => {
+                if !spacing.iter().all(|&s| s > 0. && s.is_finite()) {
+                    bail!("repetition's spacing has to be positive and finite, not {spacing}");
+                }
+
+                Box::new(Repeat {
+                    sdf: Self::sdf(shape)?,
+                    spacing: *spacing,
+                    limit: Vector3::from(limit.map(f64::from)),
+                })
+            }
+            SdfDescription::Twist { shape, rate } => {
+                Box::new(Twist::new(Self::sdf(shape)?, rate.to_radians()))
+            }
+            SdfDescription::Round { shape, radius } => {
+                if !(*radius >= 0. && radius.is_finite()) {
+                    bail!("rounding's radius has to be zero or more and finite, not {radius}");
+                }
+
+                Box::new(Round {
+                    sdf: Self::sdf(shape)?,
+                    radius: *radius,
+                })
+            }
+            SdfDescription::Mandelbulb { power, iterations } => {
+                if *power <= 1. {
+                    bail!("mandelbulb's power of {power} isn't greater than 1");
+                }
+
+                Box::new(Mandelbulb {
+                    power: *power,
+                    iterations: *iterations,
+                })
+            }
+        })
+    }
+
+    /// Check that a cylinder or cone isn't flat or infinitely thin.
+    fn check_size(shape: &str, radius: f64, height: f64) -> Result<()> {
+        if !(radius > 0. && radius.is_finite()) {
+            bail!("{shape}'s radius has to be positive and finite, not {radius}");
+        }
+        if !(height > 0. && height.is_finite()) {
+            bail!("{shape}'s height has to be positive and finite, not {height}");
+        }
+
+        Ok(())
+    }
+
+    /// Normalize the normal of a flat shape, which can't be zero.
+    fn normal(normal: &Vector3<f64>, shape: &str) -> Result<Unit<Vector3<f64>>> {
+        Unit::try_new(*normal, 0.).ok_or_else(|| anyhow!("{shape} has a zero-length normal"))
+    }
+
+    /// Look up a prototype by name, building it the first time it's used.
+    fn prototype(&mut self, name: &'a str) -> Result<Arc<dyn Hittable>> {
+        if let Some(prototype) = self.prototypes.get(name) {
+            return Ok(Arc::clone(prototype));
+        }
+
+        let objects = self
+            .description
+            .prototypes
+            .get(name)
+            .with_context(|| format!("unknown prototype `{name}`"))?;
+
+        if self.prototypes_in_progress.contains(&name) {
+            bail!("the prototype `{name}` contains an instance of itself");
+        }
+
+        self.prototypes_in_progress.push(name);
+        let prototype = self.objects(objects, &self.spans.prototypes[name]);
+        self.prototypes_in_progress.pop();
+        let prototype: Arc<dyn Hittable> = Arc::new(Bvh::new(
+            prototype.with_context(|| format!("in the prototype `{name}`"))?,
+        ));
+
+        self.prototypes.insert(name, Arc::clone(&prototype));
+        Ok(prototype)
+    }
+
+    /// Look up a material by name, building it the first time it's used.
+    fn material(&mut self, name: &'a str) -> Result<Box<dyn Material>> {
+        if let Some(material) = self.materials.get(name) {
+            return Ok(material.clone());
+        }
+
+        let description = self
+            .description
+            .materials
+            .get(name)
+            .with_context(|| format!("unknown material `{name}`"))?;
+        let line = self.line_of(&self.spans.materials[name]);
+        let material = self
+            .build_material(description)
+            .with_context(|| format!("in the material `{name}` at line {line}"))?;
+
+        self.materials.insert(name, material.clone());
+        Ok(material)
+    }
+
+    fn build_material(
+        &mut self,
+        description: &'a MaterialDescription,
+    ) -> Result<Box<dyn Material>> {
+        Ok(match description {
+            MaterialDescription::Lambertian { albedo } => {
+                Box::new(Lambertian::with_texture(self.color_or_texture(albedo)?))
+            }
+            MaterialDescription::Metal { albedo, fuzz } => {
+                if !(0. ..=1.).contains(fuzz) {
+                    bail!("metal's fuzz has to be between 0 and 1, not {fuzz}");
+                }
+
+                Box::new(Metal::with_texture(self.color_or_texture(albedo)?, *fuzz))
+            }
+            MaterialDescription::Dielectric {
+                refractive_index,
+                tint,
+            } => {
+                if !(*refractive_index > 0. && refractive_index.is_finite()) {
+                    bail!(
+                        "dielectric's refractive index has to be positive and finite, not \
+                         {refractive_index}"
+                    );
+                }
+
+                match tint {
+                    Some(tint) => Box::new(Dielectric::new_with_tint(*refractive_index, *tint)),
+                    None => Box::new(Dielectric::new(*refractive_index)),
+                }
+            }
+            MaterialDescription::DiffuseLight { color, intensity } => {
+                Box::new(DiffuseLight::new(*color, *intensity))
+            }
+        })
+    }
+
+    fn color_or_texture(&mut self, value: &'a ColorOrTexture) -> Result<Arc<dyn Texture>> {
+        match value {
+            ColorOrTexture::Color(color) => Ok(Arc::new(textures::SolidColor::new(*color))),
+            ColorOrTexture::Texture(name) => self.texture(name),
+        }
+    }
+
+    /// Look up a texture by name, building it the first time it's used.
+    fn texture(&mut self, name: &'a str) -> Result<Arc<dyn Texture>> {
+        if let Some(texture) = self.textures.get(name) {
+            return Ok(Arc::clone(texture));
+        }
+
+        let description = self
+            .description
+            .textures
+            .get(name)
+            .with_context(|| format!("unknown texture `{name}`"))?;
+        let line = self.line_of(&self.spans.textures[name]);
+
+        if self.textures_in_progress.contains(&name) {
+            bail!("the texture `{name}` at line {line} refers to itself");
+        }
+
+        self.textures_in_progress.push(name);
+        let texture = self.build_texture(description);
+        self.textures_in_progress.pop();
+        let texture = texture.with_context(|| format!("in the texture `{name}` at line {line}"))?;
+
+        self.textures.insert(name, Arc::clone(&texture));
+        Ok(texture)
+    }
+
+    fn build_texture(&mut self, description: &'a TextureDescription) -> Result<Arc<dyn Texture>> {
+        Ok(match description {
+            TextureDescription::SolidColor { color } => Arc::new(textures::SolidColor::new(*color)),
+            TextureDescription::Checker { scale, even, odd } => {
+                if !(*scale > 0. && scale.is_finite()) {
+                    bail!("checker's scale has to be positive and finite, not {scale}");
+                }
+
+                Arc::new(Checker::new(
+                    *scale,
+                    self.color_or_texture(even)?,
+                    self.color_or_texture(odd)?,
+                ))
+            }
+            TextureDescription::Image { path } => self.assets.image(&self.base_dir.join(path))?,
+        })
+    }
+}
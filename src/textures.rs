@@ -0,0 +1,13 @@
+mod checker;
+mod image_texture;
+mod solid_color;
+mod texture;
+
+#[allow(unused_imports)]
+pub use checker::*;
+#[allow(unused_imports)]
+pub use image_texture::*;
+#[allow(unused_imports)]
+pub use solid_color::*;
+#[allow(unused_imports)]
+pub use texture::*;
@@ -0,0 +1,42 @@
+use std::sync::Arc;
+
+use na::{Point2, Point3, Vector3};
+
+use crate::textures::Texture;
+
+/// A three-dimensional checkerboard of cubes alternating between two textures.
+///
+/// Because the pattern is defined in space rather than on the surface, objects look like
+/// they've been carved out of it.
+#[derive(Clone, Debug)]
+pub struct Checker {
+    inverse_scale: f64,
+    even: Arc<dyn Texture>,
+    odd: Arc<dyn Texture>,
+}
+
+impl Checker {
+    /// Create a checkerboard whose cubes are `scale` units across.
+    #[allow(unused)]
+    pub fn new(scale: f64, even: Arc<dyn Texture>, odd: Arc<dyn Texture>) -> Self {
+        Checker {
+            inverse_scale: 1. / scale,
+            even,
+            odd,
+        }
+    }
+}
+
+impl Texture for Checker {
+    fn value(&self, uv: &Point2<f64>, point: &Point3<f64>) -> Vector3<f64> {
+        #[allow(clippy::cast_possible_truncation)]
+        let cell = |x: f64| (self.inverse_scale * x).floor() as i64;
+        let sum = cell(point.x) + cell(point.y) + cell(point.z);
+
+        if sum.rem_euclid(2) == 0 {
+            self.even.value(uv, point)
+        } else {
+            self.odd.value(uv, point)
+        }
+    }
+}
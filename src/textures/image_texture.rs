@@ -0,0 +1,83 @@
+use std::fmt;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use image::Rgb32FImage;
+use na::{Point2, Point3, Vector3};
+use palette::{LinSrgb, Srgb};
+
+use crate::textures::Texture;
+
+/// A texture that maps an image onto the surface, using the hit's texture coordinates.
+///
+/// The image repeats outside of the unit square, and is sampled with bilinear filtering.
+pub struct ImageTexture {
+    /// The image's pixels, converted to linear light.
+    image: Rgb32FImage,
+}
+
+impl ImageTexture {
+    /// Load an image file, which is assumed to be sRGB encoded unless it's a floating
+    /// point format.
+    #[allow(unused)]
+    pub fn load(path: &Path) -> Result<Self> {
+        let image = image::open(path)
+            .with_context(|| format!("couldn't load texture {}", path.display()))?;
+        let is_linear = matches!(
+            image.color(),
+            image::ColorType::Rgb32F | image::ColorType::Rgba32F
+        );
+
+        let mut image = image.into_rgb32f();
+        if !is_linear {
+            for pixel in image.pixels_mut() {
+                let [r, g, b] = pixel.0;
+                let linear: LinSrgb<f32> = Srgb::new(r, g, b).into_linear();
+                pixel.0 = [linear.red, linear.green, linear.blue];
+            }
+        }
+
+        Ok(Self::new(image))
+    }
+
+    /// Create a texture from an image that is already in linear light.
+    #[allow(unused)]
+    pub fn new(image: Rgb32FImage) -> Self {
+        ImageTexture { image }
+    }
+}
+
+impl fmt::Debug for ImageTexture {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ImageTexture")
+            .field("dimensions", &self.image.dimensions())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Texture for ImageTexture {
+    fn value(&self, uv: &Point2<f64>, _point: &Point3<f64>) -> Vector3<f64> {
+        let width = self.image.width();
+        let height = self.image.height();
+
+        // Texture coordinates have v going up, but images are stored top row first.
+        let x = uv.x * f64::from(width) - 0.5;
+        let y = (1. - uv.y) * f64::from(height) - 0.5;
+        let x0 = x.floor();
+        let y0 = y.floor();
+        let tx = x - x0;
+        let ty = y - y0;
+
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let texel = |x: f64, y: f64| {
+            let x = (x as i64).rem_euclid(i64::from(width)) as u32;
+            let y = (y as i64).rem_euclid(i64::from(height)) as u32;
+            let [r, g, b] = self.image.get_pixel(x, y).0;
+            Vector3::new(f64::from(r), f64::from(g), f64::from(b))
+        };
+
+        let top = texel(x0, y0).lerp(&texel(x0 + 1., y0), tx);
+        let bottom = texel(x0, y0 + 1.).lerp(&texel(x0 + 1., y0 + 1.), tx);
+        top.lerp(&bottom, ty)
+    }
+}
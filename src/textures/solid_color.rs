@@ -0,0 +1,22 @@
+use na::{Point2, Point3, Vector3};
+
+use crate::textures::Texture;
+
+/// A texture that's the same color everywhere.
+#[derive(Copy, Clone, Debug)]
+pub struct SolidColor {
+    color: Vector3<f64>,
+}
+
+impl SolidColor {
+    #[allow(unused)]
+    pub fn new(color: Vector3<f64>) -> Self {
+        SolidColor { color }
+    }
+}
+
+impl Texture for SolidColor {
+    fn value(&self, _uv: &Point2<f64>, _point: &Point3<f64>) -> Vector3<f64> {
+        self.color
+    }
+}
@@ -0,0 +1,9 @@
+use std::fmt::Debug;
+
+use na::{Point2, Point3, Vector3};
+
+/// A color that varies over the surface of an object.
+pub trait Texture: Send + Sync + Debug {
+    /// Get the color at the given surface coordinates and point in space.
+    fn value(&self, uv: &Point2<f64>, point: &Point3<f64>) -> Vector3<f64>;
+}
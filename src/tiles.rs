@@ -0,0 +1,144 @@
+//! Splitting the image into tiles, the units of work that rendering is scheduled in.
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+/// The orders tiles can be rendered in.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TileOrder {
+    /// Row by row, from the top left.
+    Scanline,
+    /// Spiraling outwards from the center, so the middle of the image fills in first.
+    #[default]
+    Spiral,
+    /// Along a Hilbert curve, which keeps consecutive tiles next to each other.
+    Hilbert,
+}
+
+/// A rectangle of pixels rendered as one unit.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Tile {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Tile {
+    /// The number of pixels in the tile.
+    pub fn len(&self) -> usize {
+        self.width as usize * self.height as usize
+    }
+}
+
+/// Split an image into tiles of at most `tile_size` pixels square, in the given order.
+pub fn tiles(width: u32, height: u32, tile_size: u32, order: TileOrder) -> Vec<Tile> {
+    if width == 0 || height == 0 {
+        return Vec::new();
+    }
+
+    let tile_size = tile_size.max(1);
+    let columns = width.div_ceil(tile_size);
+    let rows = height.div_ceil(tile_size);
+
+    let positions = match order {
+        TileOrder::Scanline => (0..rows)
+            .flat_map(|row| (0..columns).map(move |column| (column, row)))
+            .collect(),
+        TileOrder::Spiral => spiral(columns, rows),
+        TileOrder::Hilbert => hilbert(columns, rows),
+    };
+
+    positions
+        .into_iter()
+        .map(|(column, row)| {
+            let x = column * tile_size;
+            let y = row * tile_size;
+
+            Tile {
+                x,
+                y,
+                width: tile_size.min(width - x),
+                height: tile_size.min(height - y),
+            }
+        })
+        .collect()
+}
+
+/// The cells of a grid in a square spiral, going right, down, left and up around the
+/// center.
+fn spiral(columns: u32, rows: u32) -> Vec<(u32, u32)> {
+    let count = columns as usize * rows as usize;
+    let mut positions = Vec::with_capacity(count);
+    let (mut x, mut y) = (i64::from((columns - 1) / 2), i64::from((rows - 1) / 2));
+    let directions = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+    let mut leg_length = 1;
+
+    let visit = |positions: &mut Vec<_>, x: i64, y: i64| {
+        if let (Ok(x), Ok(y)) = (u32::try_from(x), u32::try_from(y)) {
+            if x < columns && y < rows {
+                positions.push((x, y));
+            }
+        }
+    };
+    visit(&mut positions, x, y);
+
+    // Every two legs of the spiral, the legs get one cell longer.
+    for leg in 0.. {
+        if positions.len() >= count {
+            break;
+        }
+
+        let (dx, dy) = directions[leg % 4];
+        for _ in 0..leg_length {
+            x += dx;
+            y += dy;
+            visit(&mut positions, x, y);
+        }
+
+        if leg % 2 == 1 {
+            leg_length += 1;
+        }
+    }
+
+    positions
+}
+
+/// The cells of a grid in the order a Hilbert curve over the smallest enclosing
+/// power-of-two square visits them.
+fn hilbert(columns: u32, rows: u32) -> Vec<(u32, u32)> {
+    let side = columns.max(rows).next_power_of_two();
+
+    (0..u64::from(side) * u64::from(side))
+        .map(|d| hilbert_position(side, d))
+        .filter(|&(x, y)| x < columns && y < rows)
+        .collect()
+}
+
+/// The cell at distance `d` along the Hilbert curve through a `side` by `side` grid.
+fn hilbert_position(side: u32, mut d: u64) -> (u32, u32) {
+    let (mut x, mut y) = (0, 0);
+    let mut s = 1;
+
+    while s < side {
+        #[allow(clippy::cast_possible_truncation)]
+        let (rx, ry) = ((1 & (d / 2)) as u32, (1 & (d ^ (d / 2))) as u32);
+
+        // Rotate the quadrant so the curves inside it join up.
+        if ry == 0 {
+            if rx == 1 {
+                x = s - 1 - x;
+                y = s - 1 - y;
+            }
+            (x, y) = (y, x);
+        }
+
+        x += s * rx;
+        y += s * ry;
+        d /= 4;
+        s *= 2;
+    }
+
+    (x, y)
+}
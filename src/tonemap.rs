@@ -0,0 +1,159 @@
+use clap::ValueEnum;
+use image::{Rgb, Rgb32FImage, RgbImage};
+use na::{Matrix3, Vector3};
+use serde::Deserialize;
+
+/// Curves for squeezing scene radiance into the displayable range.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToneMapping {
+    /// Clip every channel to [0, 1].
+    #[default]
+    Clamp,
+    /// Reinhard's `L / (1 + L)` operator, applied to luminance.
+    Reinhard,
+    /// Reinhard's operator with a white point, which maps to exactly 1.
+    ExtendedReinhard,
+    /// Stephen Hill's fit of the ACES reference rendering transform and sRGB output
+    /// transform.
+    Aces,
+    /// Troy Sobotka's AgX, using the common polynomial approximation of its base contrast
+    /// curve.
+    Agx,
+}
+
+/// The stage turning linear radiance into 8-bit sRGB for display.
+#[derive(Copy, Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ToneMapper {
+    pub tone_mapping: ToneMapping,
+    /// The exposure adjustment applied before tone mapping, in stops.
+    pub exposure: f64,
+    /// The luminance mapped to pure white by [`ToneMapping::ExtendedReinhard`].
+    pub white_point: f64,
+}
+
+impl Default for ToneMapper {
+    fn default() -> Self {
+        Self {
+            tone_mapping: ToneMapping::default(),
+            exposure: 0.,
+            white_point: 4.,
+        }
+    }
+}
+
+impl ToneMapper {
+    /// Map a linear scene-referred color to a linear display-referred one in [0, 1].
+    pub fn map(&self, color: Vector3<f64>) -> Vector3<f64> {
+        let color = color.map(|c| c.max(0.)) * self.exposure.exp2();
+
+        let mapped = match self.tone_mapping {
+            ToneMapping::Clamp => color,
+            ToneMapping::Reinhard => scale_luminance(color, |l| l / (1. + l)),
+            ToneMapping::ExtendedReinhard => {
+                let white_squared = self.white_point * self.white_point;
+                scale_luminance(color, |l| l * (1. + l / white_squared) / (1. + l))
+            }
+            ToneMapping::Aces => aces(color),
+            ToneMapping::Agx => agx(color),
+        };
+
+        mapped.map(|c| c.clamp(0., 1.))
+    }
+
+    /// Tone map a linear framebuffer into an 8-bit sRGB image.
+    pub fn map_image(&self, image: &Rgb32FImage) -> RgbImage {
+        RgbImage::from_fn(image.width(), image.height(), |x, y| {
+            let Rgb([r, g, b]) = *image.get_pixel(x, y);
+            let mapped = self.map(Vector3::new(f64::from(r), f64::from(g), f64::from(b)));
+
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            let encode = |c: f64| (255. * srgb_oetf(c) + 0.5) as u8;
+            Rgb([encode(mapped.x), encode(mapped.y), encode(mapped.z)])
+        })
+    }
+}
+
+/// The Rec. 709 luminance of a linear color.
+pub fn luminance(color: &Vector3<f64>) -> f64 {
+    color.dot(&Vector3::new(0.2126, 0.7152, 0.0722))
+}
+
+/// Apply a curve to the luminance of a color, keeping its chromaticity.
+fn scale_luminance(color: Vector3<f64>, curve: impl Fn(f64) -> f64) -> Vector3<f64> {
+    let l = luminance(&color);
+    if l <= 0. {
+        return Vector3::zeros();
+    }
+
+    color * (curve(l) / l)
+}
+
+fn aces(color: Vector3<f64>) -> Vector3<f64> {
+    // sRGB to the RRT's input space, with the exposure bias the fit expects baked in.
+    #[rustfmt::skip]
+    let input = Matrix3::new(
+        0.59719, 0.35458, 0.04823,
+        0.07600, 0.90834, 0.01566,
+        0.02840, 0.13383, 0.83777,
+    );
+    // The ODT's output space back to sRGB.
+    #[rustfmt::skip]
+    let output = Matrix3::new(
+         1.60475, -0.53108, -0.07367,
+        -0.10208,  1.10813, -0.00605,
+        -0.00327, -0.07276,  1.07602,
+    );
+
+    let v = input * color;
+    let fitted = v.map(|v| {
+        let a = v * (v + 0.024_578_6) - 0.000_090_537;
+        let b = v * (0.983_729 * v + 0.432_951) + 0.238_081;
+        a / b
+    });
+
+    output * fitted
+}
+
+fn agx(color: Vector3<f64>) -> Vector3<f64> {
+    const MIN_EV: f64 = -12.473_93;
+    const MAX_EV: f64 = 4.026_069;
+
+    #[rustfmt::skip]
+    let inset = Matrix3::new(
+        0.842_479_062_253_094, 0.078_433_599_999_999_2, 0.079_223_745_147_764_3,
+        0.042_328_242_261_012_3, 0.878_468_636_469_772, 0.079_166_127_460_543_4,
+        0.042_375_654_905_705_1, 0.078_433_6, 0.879_142_973_793_104,
+    );
+    #[rustfmt::skip]
+    let outset = Matrix3::new(
+        1.196_879_005_120_17, -0.098_020_881_140_136_8, -0.099_029_744_079_720_5,
+        -0.052_896_851_757_456_2, 1.151_903_129_904_17, -0.098_961_176_844_843_3,
+        -0.052_971_635_514_443_8, -0.098_043_450_117_124_1, 1.151_073_672_641_16,
+    );
+
+    let encoded = (inset * color).map(|c| {
+        let ev = c.max(1e-10).log2().clamp(MIN_EV, MAX_EV);
+        let x = (ev - MIN_EV) / (MAX_EV - MIN_EV);
+
+        // The sixth order fit of AgX's base contrast sigmoid.
+        let x2 = x * x;
+        let x4 = x2 * x2;
+        15.5 * x4 * x2 - 40.14 * x4 * x + 31.96 * x4 - 6.868 * x2 * x + 0.4298 * x2 + 0.1191 * x
+            - 0.002_32
+    });
+
+    // The curve's output is display encoded, so undo that to stay in linear light.
+    (outset * encoded).map(|c| c.max(0.).powf(2.2))
+}
+
+/// The piecewise sRGB opto-electronic transfer function, from linear light to encoded
+/// values.
+fn srgb_oetf(c: f64) -> f64 {
+    if c <= 0.003_130_8 {
+        12.92 * c
+    } else {
+        1.055 * c.powf(1. / 2.4) - 0.055
+    }
+}
@@ -1,13 +1,37 @@
-use na::{Unit, UnitVector3, Vector3};
+use na::{Point2, Unit, UnitVector3, Vector3};
 use palette::{rgb::Rgb, Hsl, IntoColor, Srgb};
-use rand::Rng;
-use std::f64::consts::TAU;
+use rand::{Rng, SeedableRng};
+use std::f64::consts::{PI, TAU};
 
-pub fn random_unit_vector() -> UnitVector3<f64> {
-    let mut rng = rand::thread_rng();
+/// The random number generator used for everything that goes into rendering an image.
+pub type SampleRng = rand_pcg::Pcg64Mcg;
 
-    let u: f64 = 2. * rng.gen::<f64>() - 1.;
-    let phi: f64 = TAU * rng.gen::<f64>();
+/// Get the random number generator for one sample of one pixel.
+///
+/// Every sample gets its own stream, derived only from the seed and its coordinates, so
+/// that an image comes out the same no matter how the work is split between threads.
+pub fn sample_rng(seed: u64, x: u32, y: u32, sample: u64) -> SampleRng {
+    SampleRng::seed_from_u64(splitmix64(pixel_seed(seed, x, y) ^ sample))
+}
+
+/// Get a seed unique to one pixel, for deriving anything else random about it from.
+pub fn pixel_seed(seed: u64, x: u32, y: u32) -> u64 {
+    let pixel = u64::from(x) | (u64::from(y) << 32);
+    splitmix64(splitmix64(seed) ^ pixel)
+}
+
+/// One step of the SplitMix64 generator, which makes a decent hash for 64-bit values.
+pub fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Map a point in [0, 1)² to a point on the unit sphere, keeping the distribution uniform.
+pub fn sample_unit_vector(sample: Point2<f64>) -> UnitVector3<f64> {
+    let u: f64 = 2. * sample.x - 1.;
+    let phi: f64 = TAU * sample.y;
 
     Unit::new_normalize(Vector3::new(
         phi.cos() * (1. - u.powf(2.)).powf(0.5),
@@ -16,16 +40,34 @@ pub fn random_unit_vector() -> UnitVector3<f64> {
     ))
 }
 
-#[allow(dead_code)]
-pub fn random_in_unit_disk() -> Vector3<f64> {
-    let mut rng = rand::thread_rng();
-
-    let theta = rng.gen_range(0. .. TAU);
-    let r = rng.gen_range(0f64 ..= 1f64).sqrt();
+/// Map a point in [0, 1)² to a point in the unit disk, keeping the distribution uniform.
+pub fn sample_unit_disk(sample: Point2<f64>) -> Vector3<f64> {
+    let theta = TAU * sample.x;
+    let r = sample.y.sqrt();
 
     Vector3::new(r * theta.cos(), r * theta.sin(), 0.)
 }
 
+/// Get the angle of `v` around the y axis, in [0, 2π], starting from -x and turning
+/// towards +z.
+pub fn azimuth(v: &Vector3<f64>) -> f64 {
+    f64::atan2(-v.z, v.x) + PI
+}
+
+/// Get two unit vectors that make a right-handed orthonormal basis with `normal`.
+pub fn orthonormal_basis(normal: &Unit<Vector3<f64>>) -> (Unit<Vector3<f64>>, Unit<Vector3<f64>>) {
+    // Start from whichever axis is furthest from the normal, to stay well conditioned.
+    let helper = if normal.x.abs() > 0.9 {
+        Vector3::y()
+    } else {
+        Vector3::x()
+    };
+    let tangent = Unit::new_normalize(helper.cross(normal));
+    let bitangent = Unit::new_normalize(normal.cross(&tangent));
+
+    (tangent, bitangent)
+}
+
 pub fn reflect_vector(v: &Vector3<f64>, n: &Vector3<f64>) -> Vector3<f64> {
     v - 2. * v.dot(n) * n
 }
@@ -34,8 +76,7 @@ pub fn color(r: f64, g: f64, b: f64) -> Vector3<f64> {
     Vector3::new(r, g, b)
 }
 
-pub fn random_color() -> Vector3<f64> {
-    let mut rng = rand::thread_rng();
+pub fn random_color(rng: &mut impl Rng) -> Vector3<f64> {
     let hue = rng.gen_range(0.0..360.0);
     let saturation = rng.gen_range(0.5..=1.0);
     let lightness = rng.gen_range(0.2..=0.8);
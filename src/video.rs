@@ -0,0 +1,192 @@
+//! Encoding the frames of an animation into a single video file as they're rendered.
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use image::codecs::gif::{GifEncoder, Repeat};
+use image::{Delay, DynamicImage, Frame, RgbImage};
+
+/// A video being written, which frames are added to one at a time.
+///
+/// The format comes from the file's extension: `.gif` and `.png` (as an animated PNG) are
+/// written directly, and anything else is handed to `ffmpeg`, which has to be on the `PATH`.
+pub struct Video {
+    path: PathBuf,
+    encoder: Encoder,
+    width: u32,
+    height: u32,
+    /// The number of the frame that has to be written next.
+    next_frame: u32,
+    /// Frames that arrived before the ones in front of them, waiting for their turn.
+    pending: BTreeMap<u32, RgbImage>,
+}
+
+enum Encoder {
+    Gif(GifEncoder<BufWriter<File>>, Delay),
+    Apng(png::Writer<BufWriter<File>>),
+    Ffmpeg(Child, BufWriter<ChildStdin>),
+}
+
+impl Video {
+    /// Start a video of `frames` frames at `frame_rate` frames per second, beginning with the
+    /// frame numbered `first_frame`.
+    ///
+    /// `codec` picks the codec `ffmpeg` encodes with, and is ignored by the other formats.
+    pub fn create(
+        path: &Path,
+        (width, height): (u32, u32),
+        first_frame: u32,
+        frames: u32,
+        frame_rate: f64,
+        codec: Option<&str>,
+    ) -> Result<Self> {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_ascii_lowercase);
+
+        let create = || -> Result<Encoder> {
+            Ok(match extension.as_deref() {
+                Some("gif") => {
+                    let mut encoder = GifEncoder::new(BufWriter::new(File::create(path)?));
+                    encoder.set_repeat(Repeat::Infinite)?;
+                    let delay =
+                        Delay::from_saturating_duration(Duration::from_secs_f64(1. / frame_rate));
+
+                    Encoder::Gif(encoder, delay)
+                }
+                Some("png" | "apng") => {
+                    let mut encoder =
+                        png::Encoder::new(BufWriter::new(File::create(path)?), width, height);
+                    encoder.set_color(png::ColorType::Rgb);
+                    encoder.set_depth(png::BitDepth::Eight);
+                    encoder.set_animated(frames, 0)?;
+
+                    // The delay is a fraction of a second, with both parts 16-bit.
+                    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                    let denominator = (100. * frame_rate).round().clamp(1., 65535.) as u16;
+                    encoder.set_frame_delay(100, denominator)?;
+
+                    Encoder::Apng(encoder.write_header()?)
+                }
+                _ => Self::spawn_ffmpeg(path, (width, height), frame_rate, codec)?,
+            })
+        };
+
+        let encoder =
+            create().with_context(|| format!("couldn't start the video {}", path.display()))?;
+
+        Ok(Self {
+            path: path.to_owned(),
+            encoder,
+            width,
+            height,
+            next_frame: first_frame,
+            pending: BTreeMap::new(),
+        })
+    }
+
+    fn spawn_ffmpeg(
+        path: &Path,
+        (width, height): (u32, u32),
+        frame_rate: f64,
+        codec: Option<&str>,
+    ) -> Result<Encoder> {
+        let mut command = Command::new("ffmpeg");
+        command
+            .args(["-y", "-loglevel", "error", "-f", "rawvideo"])
+            .args(["-pixel_format", "rgb24"])
+            .args(["-video_size", &format!("{width}x{height}")])
+            .args(["-framerate", &frame_rate.to_string()])
+            .args(["-i", "-"]);
+        if let Some(codec) = codec {
+            command.args(["-codec:v", codec]);
+        }
+        // Most players can only handle chroma subsampled video.
+        command.args(["-pix_fmt", "yuv420p"]).arg(path);
+
+        let mut child = command
+            .stdin(Stdio::piped())
+            .spawn()
+            .context("couldn't run ffmpeg")?;
+        let stdin = child.stdin.take().expect("ffmpeg's stdin is piped");
+
+        Ok(Encoder::Ffmpeg(child, BufWriter::new(stdin)))
+    }
+
+    /// Add the frame numbered `frame` to the video.
+    ///
+    /// Frames can be added in any order, and are held on to until all of the ones before them
+    /// have been added.
+    pub fn add_frame(&mut self, frame: u32, image: RgbImage) -> Result<()> {
+        if image.dimensions() != (self.width, self.height) {
+            bail!(
+                "frame {frame} is {}x{}, but the video {} is {}x{}",
+                image.width(),
+                image.height(),
+                self.path.display(),
+                self.width,
+                self.height
+            );
+        }
+
+        self.pending.insert(frame, image);
+        while let Some(image) = self.pending.remove(&self.next_frame) {
+            self.write_frame(image)
+                .with_context(|| format!("couldn't write to the video {}", self.path.display()))?;
+            self.next_frame += 1;
+        }
+
+        Ok(())
+    }
+
+    fn write_frame(&mut self, image: RgbImage) -> Result<()> {
+        match &mut self.encoder {
+            Encoder::Gif(encoder, delay) => {
+                let image = DynamicImage::ImageRgb8(image).into_rgba8();
+                encoder.encode_frame(Frame::from_parts(image, 0, 0, *delay))?;
+            }
+            Encoder::Apng(writer) => writer.write_image_data(&image)?,
+            Encoder::Ffmpeg(_, stdin) => stdin.write_all(&image)?,
+        }
+
+        Ok(())
+    }
+
+    /// Finish writing the video, once every frame has been added.
+    pub fn finish(self) -> Result<()> {
+        if let Some(frame) = self.pending.keys().next() {
+            bail!(
+                "the video {} is missing frame {}, which comes before frame {frame}",
+                self.path.display(),
+                self.next_frame
+            );
+        }
+
+        let finish = || -> Result<()> {
+            match self.encoder {
+                // The GIF's trailer is written when its encoder is dropped.
+                Encoder::Gif(..) => {}
+                Encoder::Apng(writer) => writer.finish()?,
+                Encoder::Ffmpeg(mut child, stdin) => {
+                    // Closing ffmpeg's input is what tells it the video is over.
+                    drop(stdin.into_inner()?);
+
+                    let status = child.wait()?;
+                    if !status.success() {
+                        bail!("ffmpeg failed with {status}");
+                    }
+                }
+            }
+
+            Ok(())
+        };
+
+        finish().with_context(|| format!("couldn't finish the video {}", self.path.display()))
+    }
+}